@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+pub(crate) fn expand_hook_args(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "HookArgs can only be derived for structs",
+            ))
+        }
+    };
+
+    let reads = fields.iter().enumerate().map(|(i, f)| {
+        let ty = &f.ty;
+        let index = i as u32;
+        quote! {
+            <#ty as ::frida_rs::interceptor::FromArg>::from_arg(args.get(#index))
+        }
+    });
+
+    let body = match fields {
+        Fields::Named(f) => {
+            let names = f.named.iter().map(|f| &f.ident);
+            quote! { Self { #( #names: #reads ),* } }
+        }
+        Fields::Unnamed(_) => quote! { Self( #( #reads ),* ) },
+        Fields::Unit => quote! { Self },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::frida_rs::interceptor::HookArgs for #name #ty_generics #where_clause {
+            fn from_args(args: &::frida_rs::interceptor::InvocationArgs) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn named_fields_are_read_in_order() {
+        let input: DeriveInput = parse_quote! {
+            struct Open {
+                path: CStrPtr,
+                flags: i32,
+            }
+        };
+        let expanded = expand_hook_args(&input).unwrap().to_string();
+        assert!(expanded.contains(
+            "path : < CStrPtr as :: frida_rs :: interceptor :: FromArg > :: from_arg (args . get (0u32))"
+        ));
+        assert!(expanded.contains(
+            "flags : < i32 as :: frida_rs :: interceptor :: FromArg > :: from_arg (args . get (1u32))"
+        ));
+    }
+
+    #[test]
+    fn tuple_fields_are_read_in_order() {
+        let input: DeriveInput = parse_quote! {
+            struct Read(i32, NativePointer);
+        };
+        let expanded = expand_hook_args(&input).unwrap().to_string();
+        assert!(expanded.contains(
+            "Self (< i32 as :: frida_rs :: interceptor :: FromArg > :: from_arg (args . get (0u32)) , \
+             < NativePointer as :: frida_rs :: interceptor :: FromArg > :: from_arg (args . get (1u32)))"
+        ));
+    }
+
+    #[test]
+    fn unit_struct_reads_nothing() {
+        let input: DeriveInput = parse_quote! {
+            struct Getpid;
+        };
+        let expanded = expand_hook_args(&input).unwrap().to_string();
+        assert!(expanded.contains("fn from_args"));
+        assert!(!expanded.contains("from_arg ("));
+    }
+
+    #[test]
+    fn rejects_enums() {
+        let input: DeriveInput = parse_quote! {
+            enum Args {
+                A,
+            }
+        };
+        assert!(expand_hook_args(&input).is_err());
+    }
+}
@@ -1,8 +1,15 @@
 extern crate proc_macro;
 
+mod hookargs;
+mod memory;
+
 use crate::proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+///Groups of register names that refer to the same register in a Frida CPU
+///context.
+const ALIASES: &[&[&str]] = &[&["pc", "rip", "eip"], &["sp", "rsp", "esp"]];
 
 #[proc_macro_derive(DeriveCpu)]
 pub fn derive_cpu(input: TokenStream) -> TokenStream {
@@ -12,30 +19,90 @@ pub fn derive_cpu(input: TokenStream) -> TokenStream {
 
     let expanded = match data {
         syn::Data::Struct(s) => {
-            let fields = match &s.fields {
-                syn::Fields::Named(i) => {
-                    let mut fields_call = quote! {};
-                    for field in i.named.iter() {
-                        let name = field.ident.clone().unwrap();
-
-                        if name == "sys" {
-                            continue;
-                        }
+            let registers = match &s.fields {
+                syn::Fields::Named(i) => i
+                    .named
+                    .iter()
+                    .map(|f| f.ident.clone().unwrap())
+                    .filter(|f| f != "sys")
+                    .collect::<Vec<_>>(),
+                _ => unimplemented!(),
+            };
 
-                        fields_call.extend(quote!{
-                            #name: crate::NativePointer::from_jsvalue(js_sys::Reflect::get(&m, &wasm_bindgen::prelude::JsValue::from_str(stringify!(#name))).unwrap()),
-                        })
-                    }
-                    fields_call
+            let get = |register: &syn::Ident| {
+                quote! {
+                    crate::NativePointer::from_jsvalue(js_sys::Reflect::get(&m, &wasm_bindgen::prelude::JsValue::from_str(stringify!(#register))).unwrap())
                 }
-                _ => unimplemented!(),
             };
+            let reads = registers.iter().map(get).collect::<Vec<_>>();
+
+            // Registers of the same context that share their storage, such
+            // as `pc` and `rip`.
+            let aliases = registers
+                .iter()
+                .map(|r| {
+                    let group = ALIASES
+                        .iter()
+                        .find(|g| g.iter().any(|a| r == a))
+                        .copied()
+                        .unwrap_or(&[]);
+                    registers
+                        .iter()
+                        .filter(|a| *a != r && group.iter().any(|g| *a == g))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let setters_by_name = registers
+                .iter()
+                .map(|r| format_ident!("set_{}", r))
+                .collect::<Vec<_>>();
+
+            let setters = registers
+                .iter()
+                .zip(aliases.iter())
+                .map(|(register, aliases)| {
+                    let setter = format_ident!("set_{}", register);
+                    let doc = format!("Set the `{}` register.", register);
+                    quote! {
+                        #[doc = #doc]
+                        ///
+                        ///The new value is written to the CPU context
+                        ///immediately.
+                        pub fn #setter(&mut self, value: crate::NativePointer) {
+                            js_sys::Reflect::set(
+                                &self.sys,
+                                &wasm_bindgen::prelude::JsValue::from_str(stringify!(#register)),
+                                value.to_sys(),
+                            )
+                            .unwrap();
+                            #( self.#aliases = value.clone(); )*
+                            self.#register = value;
+                        }
+                    }
+                });
+
+            let commits = registers.iter().zip(reads.iter()).zip(aliases.iter()).map(
+                |((register, read), aliases)| {
+                    quote! {
+                        if #read != self.#register {
+                            js_sys::Reflect::set(
+                                m,
+                                &wasm_bindgen::prelude::JsValue::from_str(stringify!(#register)),
+                                self.#register.to_sys(),
+                            )
+                            .unwrap();
+                            #( self.#aliases = self.#register.clone(); )*
+                        }
+                    }
+                },
+            );
 
             quote! {
                 impl crate::fromsys::FromSys<frida_rs_sys::cpu::CpuContext> for #name {
                     fn from_sys(m: frida_rs_sys::cpu::CpuContext) -> Self {
                         Self {
-                            #fields
+                            #( #registers: #reads, )*
                             sys: m
                         }
                     }
@@ -44,6 +111,42 @@ pub fn derive_cpu(input: TokenStream) -> TokenStream {
                         self.sys
                     }
                 }
+
+                impl #name {
+                    #( #setters )*
+
+                    ///Write the registers that were modified through the
+                    ///public fields back to the CPU context.
+                    ///
+                    ///Only the fields that differ from the CPU context are
+                    ///written, so aliases such as `pc` are not overwritten by
+                    ///stale values. If both a register and its alias were
+                    ///modified, the one declared last wins. This reads every
+                    ///register once, so prefer the `set_<register>` methods
+                    ///when only a few registers change.
+                    pub fn commit(&mut self) {
+                        let m = &self.sys;
+                        #( #commits )*
+                    }
+
+                    ///Get the register named `name`.
+                    pub fn register(&self, name: &str) -> Option<crate::NativePointer> {
+                        match name {
+                            #( stringify!(#registers) => Some(self.#registers.clone()), )*
+                            _ => None,
+                        }
+                    }
+
+                    ///Set the register named `name`, returning `false` if
+                    ///there is no such register.
+                    pub fn set_register(&mut self, name: &str, value: crate::NativePointer) -> bool {
+                        match name {
+                            #( stringify!(#registers) => self.#setters_by_name(value), )*
+                            _ => return false,
+                        }
+                        true
+                    }
+                }
             }
         }
         syn::Data::Enum(_) => unimplemented!(),
@@ -52,3 +155,81 @@ pub fn derive_cpu(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+///Derive `frida_rs::memory::FromMemory` and `frida_rs::memory::MemoryLayout`
+///for a struct.
+///
+///Fields are read in declaration order using the layout rules of a C struct.
+///Every field type must implement `FromMemory`. The following attributes
+///change how a field is stored:
+///
+///- `#[memory(pointer)]`: the field is stored as a pointer to the value.
+///- `#[memory(c_string)]`: the field is stored as a `char *`. The field type
+///  must be `Option<String>`.
+///- `#[memory(array = N)]`: the field is stored inline as `N` consecutive
+///  elements. The field type must be `Vec<T>` or `[T; N]`.
+///- `#[memory(offset = N)]`: the field is stored at byte offset `N` instead of
+///  the offset computed from the preceding fields.
+#[proc_macro_derive(FromMemory, attributes(memory))]
+pub fn derive_from_memory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match memory::expand_from_memory(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+///Derive `frida_rs::memory::ToMemory` for a struct.
+///
+///This accepts the same field attributes as `FromMemory` and relies on the
+///`MemoryLayout` implementation generated by it. Fields marked with
+///`#[memory(pointer)]` are written to the memory they point to. Fields marked
+///with `#[memory(c_string)]` cannot be written and are rejected.
+#[proc_macro_derive(ToMemory, attributes(memory))]
+pub fn derive_to_memory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match memory::expand_to_memory(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+///Derive `frida_rs::interceptor::HookArgs` for a struct.
+///
+///The arguments of the intercepted function are decoded into the fields in
+///declaration order, so the first field receives argument 0. Every field type
+///must implement `frida_rs::interceptor::FromArg`.
+#[proc_macro_derive(HookArgs)]
+pub fn derive_hook_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match hookargs::expand_hook_args(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+///Create a `frida_rs::memory::Pattern` from a Frida match pattern string.
+///
+///The pattern is validated at compile time, so typos such as `"4g ?? 00"`
+///are reported as compile errors instead of failing inside the instrumented
+///process.
+///
+///```ignore
+///let p = pattern!("48 8b ?? ?? e8");
+///```
+#[proc_macro]
+pub fn pattern(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+
+    let expanded = match frida_rs_pattern::parse(&lit.value()) {
+        Ok((bytes, mask)) => quote! {
+            ::frida_rs::memory::Pattern::with_mask(&[#(#bytes),*], &[#(#mask),*]).unwrap()
+        },
+        Err(e) => syn::Error::new(lit.span(), e).to_compile_error(),
+    };
+
+    TokenStream::from(expanded)
+}
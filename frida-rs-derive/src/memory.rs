@@ -0,0 +1,399 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Lit, Meta, NestedMeta, Type};
+
+///How a field is stored in the memory of the instrumented process.
+enum Kind {
+    ///The field is stored inline.
+    Inline,
+    ///The field is stored as a pointer to the value.
+    Pointer,
+    ///The field is stored as a pointer to a NUL-terminated C string.
+    CString,
+    ///The field is stored inline as `len` consecutive elements.
+    Array { len: usize, element: Box<Type> },
+}
+
+struct Field {
+    member: syn::Member,
+    ty: Type,
+    kind: Kind,
+    offset: Option<usize>,
+}
+
+impl Field {
+    ///Expression evaluating to the `(size, alignment)` of the field.
+    fn layout(&self) -> TokenStream {
+        let ty = match &self.kind {
+            Kind::Inline => &self.ty,
+            Kind::Array { len, element } => {
+                return quote! {
+                    (
+                        #len * <#element as ::frida_rs::memory::MemoryLayout>::size_of(),
+                        <#element as ::frida_rs::memory::MemoryLayout>::align_of(),
+                    )
+                };
+            }
+            Kind::Pointer | Kind::CString => {
+                return quote! {
+                    (
+                        <::frida_rs::NativePointer as ::frida_rs::memory::MemoryLayout>::size_of(),
+                        <::frida_rs::NativePointer as ::frida_rs::memory::MemoryLayout>::align_of(),
+                    )
+                };
+            }
+        };
+
+        quote! {
+            (
+                <#ty as ::frida_rs::memory::MemoryLayout>::size_of(),
+                <#ty as ::frida_rs::memory::MemoryLayout>::align_of(),
+            )
+        }
+    }
+
+    ///Statement placing the field in `layout` and evaluating to its offset.
+    fn place(&self) -> TokenStream {
+        let layout = self.layout();
+        match self.offset {
+            Some(offset) => quote! {
+                {
+                    let (size, align) = #layout;
+                    layout.field_at(#offset, size, align)
+                }
+            },
+            None => quote! {
+                {
+                    let (size, align) = #layout;
+                    layout.field(size, align)
+                }
+            },
+        }
+    }
+
+    ///Expression reading the field from `address`.
+    fn read(&self) -> TokenStream {
+        let ty = &self.ty;
+        match &self.kind {
+            Kind::Inline => quote! {
+                <#ty as ::frida_rs::memory::FromMemory>::from_memory(&address)?
+            },
+            Kind::Pointer => quote! {
+                <#ty as ::frida_rs::memory::FromMemory>::from_memory(&address.read_pointer()?)?
+            },
+            Kind::CString => quote! {
+                address.read_pointer()?.read_c_string(None)?
+            },
+            Kind::Array { len, element } => quote! {
+                {
+                    let size = <#element as ::frida_rs::memory::MemoryLayout>::size_of();
+                    let mut elements = Vec::with_capacity(#len);
+                    for i in 0..#len {
+                        elements.push(
+                            <#element as ::frida_rs::memory::FromMemory>::from_memory(
+                                &(&address + i * size),
+                            )?,
+                        );
+                    }
+                    elements
+                }
+            },
+        }
+    }
+
+    ///Statement writing the field to `address`.
+    fn write(&self) -> TokenStream {
+        let member = &self.member;
+        match &self.kind {
+            Kind::Inline => quote! {
+                ::frida_rs::memory::ToMemory::to_memory(&self.#member, &address)?;
+            },
+            Kind::Pointer => quote! {
+                ::frida_rs::memory::ToMemory::to_memory(&self.#member, &address.read_pointer()?)?;
+            },
+            // Rejected by `expand_to_memory`.
+            Kind::CString => unreachable!(),
+            Kind::Array { len, element } => quote! {
+                if self.#member.len() != #len {
+                    return Err(::frida_rs::Error::LengthMismatch {
+                        expected: #len,
+                        actual: self.#member.len(),
+                    });
+                }
+                let size = <#element as ::frida_rs::memory::MemoryLayout>::size_of();
+                for (i, element) in self.#member.iter().enumerate() {
+                    ::frida_rs::memory::ToMemory::to_memory(element, &(&address + i * size))?;
+                }
+            },
+        }
+    }
+}
+
+fn parse_int(lit: &Lit) -> syn::Result<usize> {
+    match lit {
+        Lit::Int(i) => i.base10_parse(),
+        _ => Err(syn::Error::new(lit.span(), "expected an integer literal")),
+    }
+}
+
+///Get the kind of an `array = len` field of type `Vec<T>` or `[T; len]`.
+fn array_kind(ty: &Type, len: usize) -> syn::Result<Kind> {
+    if let Type::Array(a) = ty {
+        // `[T; N]` implements the memory traits itself, the attribute only
+        // needs to agree with it.
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(i), ..
+        }) = &a.len
+        {
+            if i.base10_parse::<usize>()? != len {
+                return Err(syn::Error::new(
+                    a.len.span(),
+                    format!("array length does not match `array = {}`", len),
+                ));
+            }
+        }
+
+        return Ok(Kind::Inline);
+    }
+
+    if let Type::Path(p) = ty {
+        if let Some(segment) = p.path.segments.last() {
+            if segment.ident == "Vec" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(t)) = args.args.first() {
+                        return Ok(Kind::Array {
+                            len,
+                            element: Box::new(t.clone()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Err(syn::Error::new(
+        ty.span(),
+        "`array` fields must have the type `Vec<T>` or `[T; N]`",
+    ))
+}
+
+fn parse_field(index: usize, field: &syn::Field) -> syn::Result<Field> {
+    let member = match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(syn::Index::from(index)),
+    };
+
+    let mut kind = Kind::Inline;
+    let mut offset = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path.is_ident("memory")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new(meta.span(), "expected `#[memory(...)]`")),
+        };
+
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("pointer") => {
+                    kind = Kind::Pointer;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("c_string") => {
+                    kind = Kind::CString;
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("array") => {
+                    kind = array_kind(&field.ty, parse_int(&nv.lit)?)?;
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("offset") => {
+                    offset = Some(parse_int(&nv.lit)?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        nested.span(),
+                        "unknown memory attribute, expected one of `pointer`, `c_string`, `array = N` or `offset = N`",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(Field {
+        member,
+        ty: field.ty.clone(),
+        kind,
+        offset,
+    })
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => f.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(f) => f.unnamed.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "memory layouts can only be derived for structs",
+            ))
+        }
+    };
+
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, f)| parse_field(i, f))
+        .collect()
+}
+
+pub(crate) fn expand_from_memory(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = parse_fields(input)?;
+
+    let places = fields.iter().map(Field::place).collect::<Vec<_>>();
+    let members = fields.iter().map(|f| &f.member).collect::<Vec<_>>();
+    let vars = (0..fields.len())
+        .map(|i| format_ident!("field{}", i))
+        .collect::<Vec<_>>();
+    let reads = fields.iter().map(Field::read).collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::frida_rs::memory::MemoryLayout for #name #ty_generics #where_clause {
+            fn size_of() -> usize {
+                let mut layout = ::frida_rs::memory::Layout::new();
+                #( #places; )*
+                layout.size()
+            }
+
+            fn align_of() -> usize {
+                let mut layout = ::frida_rs::memory::Layout::new();
+                #( #places; )*
+                layout.align()
+            }
+        }
+
+        impl #impl_generics ::frida_rs::memory::FromMemory for #name #ty_generics #where_clause {
+            fn from_memory(
+                address: &::frida_rs::NativePointer,
+            ) -> Result<Self, ::frida_rs::Error> {
+                let base = address;
+                let mut layout = ::frida_rs::memory::Layout::new();
+                #(
+                    let #vars = {
+                        let address = base + #places;
+                        #reads
+                    };
+                )*
+                Ok(Self { #( #members: #vars ),* })
+            }
+        }
+    })
+}
+
+pub(crate) fn expand_to_memory(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = parse_fields(input)?;
+
+    // The string may not fit in the buffer owned by the target, and a new
+    // buffer would have to outlive the write.
+    if let Some(f) = fields.iter().find(|f| matches!(f.kind, Kind::CString)) {
+        return Err(syn::Error::new(
+            f.ty.span(),
+            "`c_string` fields cannot be written, use a `NativePointer` field to replace the string",
+        ));
+    }
+
+    let places = fields.iter().map(Field::place).collect::<Vec<_>>();
+    let writes = fields.iter().map(Field::write).collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::frida_rs::memory::ToMemory for #name #ty_generics #where_clause {
+            fn to_memory(
+                &self,
+                address: &::frida_rs::NativePointer,
+            ) -> Result<(), ::frida_rs::Error> {
+                let base = address;
+                let mut layout = ::frida_rs::memory::Layout::new();
+                #(
+                    {
+                        let address = base + #places;
+                        #writes
+                    }
+                )*
+                Ok(())
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn array_accepts_vec_and_array() {
+        let input: DeriveInput = parse_quote! {
+            struct S {
+                #[memory(array = 4)]
+                a: Vec<u16>,
+                #[memory(array = 4)]
+                b: [u16; 4],
+            }
+        };
+        let fields = parse_fields(&input).unwrap();
+        assert!(matches!(fields[0].kind, Kind::Array { len: 4, .. }));
+        assert!(matches!(fields[1].kind, Kind::Inline));
+        assert!(expand_to_memory(&input).is_ok());
+    }
+
+    #[test]
+    fn array_rejects_mismatched_length() {
+        let input: DeriveInput = parse_quote! {
+            struct S {
+                #[memory(array = 4)]
+                a: [u16; 3],
+            }
+        };
+        assert!(expand_from_memory(&input).is_err());
+    }
+
+    #[test]
+    fn array_write_checks_length() {
+        let input: DeriveInput = parse_quote! {
+            struct S {
+                #[memory(array = 4)]
+                a: Vec<u16>,
+            }
+        };
+        let expanded = expand_to_memory(&input).unwrap().to_string();
+        assert!(expanded.contains("self . a . len () != 4usize"));
+        assert!(expanded.contains("LengthMismatch"));
+    }
+
+    #[test]
+    fn array_rejects_other_types() {
+        let input: DeriveInput = parse_quote! {
+            struct S {
+                #[memory(array = 4)]
+                a: u16,
+            }
+        };
+        assert!(expand_from_memory(&input).is_err());
+    }
+
+    #[test]
+    fn c_string_cannot_be_written() {
+        let input: DeriveInput = parse_quote! {
+            struct S {
+                #[memory(c_string)]
+                name: Option<String>,
+            }
+        };
+        assert!(expand_from_memory(&input).is_ok());
+        assert!(expand_to_memory(&input).is_err());
+    }
+}
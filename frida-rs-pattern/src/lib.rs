@@ -0,0 +1,97 @@
+//!Parser for the match pattern strings used by Frida's memory scanning
+//!functions, such as `"48 8b ?? ?? e8"` or `"13 37 : 1f ff"`.
+//!
+//!This is shared by `frida_rs::memory::Pattern` and the `pattern!` macro of
+//!frida-rs-derive, so both accept exactly the same strings.
+
+///Parse hex digits and `?` wildcards, ignoring whitespace.
+fn parse_nibbles(s: &str) -> Result<Vec<Option<u8>>, String> {
+    let nibbles = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '?' => Ok(None),
+            c => c
+                .to_digit(16)
+                .map(|d| Some(d as u8))
+                .ok_or_else(|| format!("invalid character '{}' in pattern", c)),
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if nibbles.len() % 2 != 0 {
+        return Err("pattern ends with an incomplete byte".to_owned());
+    }
+
+    Ok(nibbles)
+}
+
+///Parse a Frida match pattern into its bytes and mask.
+///
+///Masked-out bits of the returned bytes are cleared. The error describes why
+///the pattern is malformed.
+pub fn parse(s: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let (pattern, mask) = match s.find(':') {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let nibbles = parse_nibbles(pattern)?;
+    if nibbles.is_empty() {
+        return Err("pattern is empty".to_owned());
+    }
+
+    let mut bytes = Vec::with_capacity(nibbles.len() / 2);
+    let mut masks = Vec::with_capacity(nibbles.len() / 2);
+    for pair in nibbles.chunks(2) {
+        bytes.push((pair[0].unwrap_or(0) << 4) | pair[1].unwrap_or(0));
+        masks.push(pair[0].map_or(0, |_| 0xf0) | pair[1].map_or(0, |_| 0x0f));
+    }
+
+    if let Some(mask) = mask {
+        let nibbles = parse_nibbles(mask)?;
+        if nibbles.len() != bytes.len() * 2 {
+            return Err("mask must have the same length as the pattern".to_owned());
+        }
+
+        for (i, pair) in nibbles.chunks(2).enumerate() {
+            match (pair[0], pair[1]) {
+                (Some(hi), Some(lo)) => {
+                    masks[i] &= (hi << 4) | lo;
+                    bytes[i] &= masks[i];
+                }
+                _ => return Err("mask cannot contain wildcards".to_owned()),
+            }
+        }
+    }
+
+    Ok((bytes, masks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert_eq!(
+            parse("48 ?? 4?").unwrap(),
+            (vec![0x48, 0x00, 0x40], vec![0xff, 0x00, 0xf0])
+        );
+    }
+
+    #[test]
+    fn mask_clears_bytes() {
+        assert_eq!(
+            parse("13 37 : 1f ff").unwrap(),
+            (vec![0x13, 0x37], vec![0x1f, 0xff])
+        );
+        assert_eq!(parse("ff : 0f").unwrap(), (vec![0x0f], vec![0x0f]));
+    }
+
+    #[test]
+    fn errors() {
+        for s in &["", " ", "4", "4g", "de ad : ff", "de : ?f"] {
+            assert!(parse(s).is_err(), "'{}' parsed", s);
+        }
+    }
+}
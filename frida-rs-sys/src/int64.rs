@@ -0,0 +1,30 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    ///Signed 64-bit integer.
+    ///
+    ///This is equivalent to the `Int64` class in the JavaScript API.
+    #[wasm_bindgen(js_name = Int64)]
+    #[derive(Debug, Clone)]
+    pub type Int64;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(s: &str) -> Int64;
+
+    #[wasm_bindgen(method, js_name = toString)]
+    pub fn to_string(this: &Int64) -> String;
+
+    ///Unsigned 64-bit integer.
+    ///
+    ///This is equivalent to the `UInt64` class in the JavaScript API.
+    #[wasm_bindgen(js_name = UInt64)]
+    #[derive(Debug, Clone)]
+    pub type UInt64;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(s: &str) -> UInt64;
+
+    #[wasm_bindgen(method, js_name = toString)]
+    pub fn to_string(this: &UInt64) -> String;
+}
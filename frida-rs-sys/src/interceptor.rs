@@ -6,7 +6,32 @@ use wasm_bindgen::JsCast;
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = Interceptor, js_name = attach)]
-    pub fn attach(target: &NativePointer, callbacks: js_sys::Object);
+    pub fn attach(target: &NativePointer, callbacks: js_sys::Object) -> InvocationListener;
+
+    #[wasm_bindgen(js_namespace = Interceptor, js_name = attach)]
+    pub fn attach_probe(target: &NativePointer, callback: &js_sys::Function) -> InvocationListener;
+
+    #[wasm_bindgen(js_namespace = Interceptor, js_name = replace, catch)]
+    pub fn replace(target: &NativePointer, replacement: &NativePointer) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_namespace = Interceptor, js_name = revert)]
+    pub fn revert(target: &NativePointer);
+
+    #[wasm_bindgen(js_namespace = Interceptor, js_name = detachAll)]
+    pub fn detach_all();
+
+    #[wasm_bindgen(js_namespace = Interceptor, js_name = flush)]
+    pub fn flush();
+
+    ///Listener returned by [`attach`][attach].
+    ///
+    ///[attach]: attach
+    #[wasm_bindgen(js_name = InvocationListener)]
+    #[derive(Debug)]
+    pub type InvocationListener;
+
+    #[wasm_bindgen(method)]
+    pub fn detach(this: &InvocationListener);
 
     ///Arguments to an invocation of the intercepted function.
     ///
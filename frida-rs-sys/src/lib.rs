@@ -3,10 +3,13 @@ pub mod console;
 pub mod cpu;
 pub mod debugsymbol;
 pub mod frida;
+pub mod int64;
 pub mod interceptor;
 pub mod memory;
+pub mod memoryaccessmonitor;
 pub mod module;
 pub mod modulemap;
+pub mod nativefunction;
 pub mod nativepointer;
 pub mod process;
 pub mod range;
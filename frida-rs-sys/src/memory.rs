@@ -3,6 +3,55 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = Memory, js_name = scanSync)]
-    pub fn scan(address: &NativePointer, size: usize, pattern: String) -> js_sys::Array;
+    #[wasm_bindgen(js_namespace = Memory, js_name = scanSync, catch)]
+    pub fn scan(
+        address: &NativePointer,
+        size: usize,
+        pattern: String,
+    ) -> Result<js_sys::Array, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = scan, catch)]
+    pub fn scan_async(
+        address: &NativePointer,
+        size: usize,
+        pattern: String,
+        callbacks: &js_sys::Object,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = alloc, catch)]
+    pub fn alloc(size: usize) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = alloc, catch)]
+    pub fn alloc_with_options(
+        size: usize,
+        options: &js_sys::Object,
+    ) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = allocUtf8String, catch)]
+    pub fn alloc_utf8_string(s: &str) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = allocUtf16String, catch)]
+    pub fn alloc_utf16_string(s: &str) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = allocAnsiString, catch)]
+    pub fn alloc_ansi_string(s: &str) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = protect)]
+    pub fn protect(address: &NativePointer, size: usize, protection: &str) -> bool;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = queryProtection, catch)]
+    pub fn query_protection(address: &NativePointer) -> Result<String, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = patchCode, catch)]
+    pub fn patch_code(
+        address: &NativePointer,
+        size: usize,
+        apply: &js_sys::Function,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = copy, catch)]
+    pub fn copy(dst: &NativePointer, src: &NativePointer, n: usize) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_namespace = Memory, js_name = dup, catch)]
+    pub fn dup(address: &NativePointer, size: usize) -> Result<NativePointer, JsValue>;
 }
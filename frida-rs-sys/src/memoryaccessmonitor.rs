@@ -0,0 +1,35 @@
+use crate::nativepointer::NativePointer;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = MemoryAccessMonitor, js_name = enable, catch)]
+    pub fn enable(ranges: &js_sys::Array, callbacks: &js_sys::Object) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(js_namespace = MemoryAccessMonitor, js_name = disable)]
+    pub fn disable();
+
+    #[wasm_bindgen(js_name = MemoryAccessDetails)]
+    pub type MemoryAccessDetails;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn operation(this: &MemoryAccessDetails) -> String;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn from(this: &MemoryAccessDetails) -> NativePointer;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn address(this: &MemoryAccessDetails) -> NativePointer;
+
+    #[wasm_bindgen(method, getter, js_name = rangeIndex)]
+    pub fn range_index(this: &MemoryAccessDetails) -> usize;
+
+    #[wasm_bindgen(method, getter, js_name = pageIndex)]
+    pub fn page_index(this: &MemoryAccessDetails) -> usize;
+
+    #[wasm_bindgen(method, getter, js_name = pagesCompleted)]
+    pub fn pages_completed(this: &MemoryAccessDetails) -> usize;
+
+    #[wasm_bindgen(method, getter, js_name = pagesTotal)]
+    pub fn pages_total(this: &MemoryAccessDetails) -> usize;
+}
@@ -0,0 +1,33 @@
+use crate::nativepointer::NativePointer;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    ///Callable function at a native address.
+    ///
+    ///This is equivalent to the `NativeFunction` class in the JavaScript API.
+    #[wasm_bindgen(js_name = NativeFunction, extends = NativePointer, extends = js_sys::Function)]
+    #[derive(Debug, Clone)]
+    pub type NativeFunction;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        address: &NativePointer,
+        return_type: &str,
+        argument_types: &js_sys::Array,
+    ) -> NativeFunction;
+
+    ///Native function backed by a JavaScript function.
+    ///
+    ///This is equivalent to the `NativeCallback` class in the JavaScript API.
+    #[wasm_bindgen(js_name = NativeCallback, extends = NativePointer)]
+    #[derive(Debug, Clone)]
+    pub type NativeCallback;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        func: &js_sys::Function,
+        return_type: &str,
+        argument_types: &js_sys::Array,
+    ) -> NativeCallback;
+}
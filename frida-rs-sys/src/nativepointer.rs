@@ -1,3 +1,4 @@
+use crate::int64::{Int64, UInt64};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -6,7 +7,7 @@ extern "C" {
     ///
     ///This is equivalent to the `NativePointer` class in the JavaScript API.
     #[wasm_bindgen(js_name = NativePointer)]
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub type NativePointer;
 
     #[wasm_bindgen(constructor)]
@@ -15,9 +16,183 @@ extern "C" {
     #[wasm_bindgen(constructor)]
     pub fn from_i32(s: i32) -> NativePointer;
 
-    #[wasm_bindgen(method, js_name = readU8)]
-    pub fn read_u8(this: &NativePointer) -> u8;
+    #[wasm_bindgen(method, js_name = isNull)]
+    pub fn is_null_pointer(this: &NativePointer) -> bool;
+
+    #[wasm_bindgen(method)]
+    pub fn add(this: &NativePointer, rhs: &JsValue) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn sub(this: &NativePointer, rhs: &JsValue) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn and(this: &NativePointer, rhs: &JsValue) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn or(this: &NativePointer, rhs: &JsValue) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn xor(this: &NativePointer, rhs: &JsValue) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn shl(this: &NativePointer, n: u32) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn shr(this: &NativePointer, n: u32) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn not(this: &NativePointer) -> NativePointer;
+
+    #[wasm_bindgen(method)]
+    pub fn equals(this: &NativePointer, rhs: &JsValue) -> bool;
+
+    #[wasm_bindgen(method)]
+    pub fn compare(this: &NativePointer, rhs: &JsValue) -> i32;
+
+    #[wasm_bindgen(method, js_name = readS8, catch)]
+    pub fn read_s8(this: &NativePointer) -> Result<i8, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readU8, catch)]
+    pub fn read_u8(this: &NativePointer) -> Result<u8, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readS16, catch)]
+    pub fn read_s16(this: &NativePointer) -> Result<i16, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readU16, catch)]
+    pub fn read_u16(this: &NativePointer) -> Result<u16, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readS32, catch)]
+    pub fn read_s32(this: &NativePointer) -> Result<i32, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readU32, catch)]
+    pub fn read_u32(this: &NativePointer) -> Result<u32, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readS64, catch)]
+    pub fn read_s64(this: &NativePointer) -> Result<Int64, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readU64, catch)]
+    pub fn read_u64(this: &NativePointer) -> Result<UInt64, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readShort, catch)]
+    pub fn read_short(this: &NativePointer) -> Result<i16, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readUShort, catch)]
+    pub fn read_ushort(this: &NativePointer) -> Result<u16, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readInt, catch)]
+    pub fn read_int(this: &NativePointer) -> Result<i32, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readUInt, catch)]
+    pub fn read_uint(this: &NativePointer) -> Result<u32, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readLong, catch)]
+    pub fn read_long(this: &NativePointer) -> Result<Int64, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readULong, catch)]
+    pub fn read_ulong(this: &NativePointer) -> Result<UInt64, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readFloat, catch)]
+    pub fn read_float(this: &NativePointer) -> Result<f32, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readDouble, catch)]
+    pub fn read_double(this: &NativePointer) -> Result<f64, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readPointer, catch)]
+    pub fn read_pointer(this: &NativePointer) -> Result<NativePointer, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readByteArray, catch)]
+    pub fn read_byte_array(
+        this: &NativePointer,
+        length: usize,
+    ) -> Result<js_sys::ArrayBuffer, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readCString, catch)]
+    pub fn read_c_string(this: &NativePointer, size: i32) -> Result<Option<String>, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readUtf8String, catch)]
+    pub fn read_utf8_string(this: &NativePointer, size: i32) -> Result<Option<String>, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readUtf16String, catch)]
+    pub fn read_utf16_string(this: &NativePointer, length: i32) -> Result<Option<String>, JsValue>;
+
+    #[wasm_bindgen(method, js_name = readAnsiString, catch)]
+    pub fn read_ansi_string(this: &NativePointer, size: i32) -> Result<Option<String>, JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeS8, catch)]
+    pub fn write_s8(this: &NativePointer, value: i8) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeU8, catch)]
+    pub fn write_u8(this: &NativePointer, value: u8) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeS16, catch)]
+    pub fn write_s16(this: &NativePointer, value: i16) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeU16, catch)]
+    pub fn write_u16(this: &NativePointer, value: u16) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeS32, catch)]
+    pub fn write_s32(this: &NativePointer, value: i32) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeU32, catch)]
+    pub fn write_u32(this: &NativePointer, value: u32) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeS64, catch)]
+    pub fn write_s64(this: &NativePointer, value: &Int64) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeU64, catch)]
+    pub fn write_u64(this: &NativePointer, value: &UInt64) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeShort, catch)]
+    pub fn write_short(this: &NativePointer, value: i16) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeUShort, catch)]
+    pub fn write_ushort(this: &NativePointer, value: u16) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeInt, catch)]
+    pub fn write_int(this: &NativePointer, value: i32) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeUInt, catch)]
+    pub fn write_uint(this: &NativePointer, value: u32) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeLong, catch)]
+    pub fn write_long(this: &NativePointer, value: &Int64) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeULong, catch)]
+    pub fn write_ulong(this: &NativePointer, value: &UInt64) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeFloat, catch)]
+    pub fn write_float(this: &NativePointer, value: f32) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeDouble, catch)]
+    pub fn write_double(this: &NativePointer, value: f64) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writePointer, catch)]
+    pub fn write_pointer(this: &NativePointer, value: &NativePointer) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeByteArray, catch)]
+    pub fn write_byte_array(
+        this: &NativePointer,
+        bytes: &js_sys::ArrayBuffer,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeUtf8String, catch)]
+    pub fn write_utf8_string(this: &NativePointer, s: &str) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeUtf16String, catch)]
+    pub fn write_utf16_string(this: &NativePointer, s: &str) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = writeAnsiString, catch)]
+    pub fn write_ansi_string(this: &NativePointer, s: &str) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = toInt32)]
+    pub fn to_int32(this: &NativePointer) -> i32;
+
+    #[wasm_bindgen(method, js_name = toUInt32)]
+    pub fn to_uint32(this: &NativePointer) -> u32;
 
     #[wasm_bindgen(method, js_name = toString)]
     pub fn to_string(this: &NativePointer) -> String;
+
+    #[wasm_bindgen(method, js_name = toString)]
+    pub fn to_string_radix(this: &NativePointer, radix: u32) -> String;
 }
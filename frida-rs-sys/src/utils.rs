@@ -4,4 +4,8 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 extern "C" {
     pub fn this_wrap(f: &Function) -> Function;
+
+    ///Wrap `f` in a function that passes its arguments to `f` as a single
+    ///array.
+    pub fn args_wrap(f: &Function) -> Function;
 }
@@ -4,12 +4,24 @@
 //!at various points in Frida usage. As registers are architecture dependent,
 //!Frida APIs will return a [`CpuContext`](CpuContext) has to be unwrapped to
 //!the appropriate struct.
+//!
+//!Registers can be modified with the generated `set_<register>` methods, which
+//!write to the CPU context immediately, or by assigning to the fields and
+//!calling `commit`. Modifications only take effect where Frida allows writing
+//!to the CPU context, such as in Interceptor callbacks.
+use crate::error::Error;
 use crate::fromsys::FromSys;
 use crate::nativepointer::NativePointer;
 use frida_rs_derive::*;
 use frida_rs_sys::cpu;
+use serde_derive::Serialize;
 
-#[derive(Debug)]
+///CPU context of the instrumented process.
+///
+///This serializes to the register values of the contained context, like the
+///`CpuContext` object in the JavaScript API.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
 pub enum CpuContext {
     Ia32CpuContext(Ia32CpuContext),
     X64CpuContext(X64CpuContext),
@@ -19,6 +31,237 @@ pub enum CpuContext {
     PortableCpuContext(PortableCpuContext),
 }
 
+///Location of a function argument.
+#[derive(Debug, PartialEq, Eq)]
+enum ArgLocation {
+    Register(&'static str),
+    ///Offset from the stack pointer.
+    Stack(usize),
+}
+
+impl ArgLocation {
+    ///Locate argument `n` given the argument registers of an ABI, the offset
+    ///of the first stack argument and the size of a stack slot.
+    fn new(registers: &[&'static str], n: usize, stack_offset: usize, slot: usize) -> Self {
+        match registers.get(n) {
+            Some(r) => Self::Register(r),
+            None => Self::Stack(stack_offset + (n - registers.len()) * slot),
+        }
+    }
+}
+
+const SYSV_ARGS: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+const WIN64_ARGS: [&str; 4] = ["rcx", "rdx", "r8", "r9"];
+const ARM_ARGS: [&str; 4] = ["r0", "r1", "r2", "r3"];
+const ARM64_ARGS: [&str; 8] = ["x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7"];
+const MIPS_ARGS: [&str; 4] = ["a0", "a1", "a2", "a3"];
+
+///Calling convention used to locate function arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallingConvention {
+    SysV,
+    Win64,
+    Cdecl,
+    Aapcs32,
+    Aapcs64,
+    O32,
+}
+
+impl CallingConvention {
+    ///Locate pointer-sized argument `n`.
+    ///
+    ///Stack offsets assume that the stack pointer is the one at the entry of
+    ///the function.
+    fn arg_location(self, n: usize) -> ArgLocation {
+        match self {
+            Self::SysV => ArgLocation::new(&SYSV_ARGS, n, 8, 8),
+            // The return address and 32 bytes of shadow space precede the
+            // stack arguments on Windows.
+            Self::Win64 => ArgLocation::new(&WIN64_ARGS, n, 0x28, 8),
+            Self::Cdecl => ArgLocation::new(&[], n, 4, 4),
+            Self::Aapcs32 => ArgLocation::new(&ARM_ARGS, n, 0, 4),
+            Self::Aapcs64 => ArgLocation::new(&ARM64_ARGS, n, 0, 8),
+            // o32 reserves stack space for the register arguments.
+            Self::O32 => ArgLocation::new(&MIPS_ARGS, n, 16, 4),
+        }
+    }
+}
+
+thread_local! {
+    // The platform cannot change while the script runs.
+    static IS_WINDOWS: bool = crate::process::get_platform() == "windows";
+}
+
+impl CpuContext {
+    fn unsupported(&self) -> Error {
+        Error::UnsupportedArchitecture(crate::process::get_arch().to_string())
+    }
+
+    ///Get the calling convention of the platform.
+    fn calling_convention(&self) -> Result<CallingConvention, Error> {
+        Ok(match self {
+            Self::X64CpuContext(_) if IS_WINDOWS.with(|w| *w) => CallingConvention::Win64,
+            Self::X64CpuContext(_) => CallingConvention::SysV,
+            Self::Ia32CpuContext(_) => CallingConvention::Cdecl,
+            Self::ArmCpuContext(_) => CallingConvention::Aapcs32,
+            Self::Arm64CpuContext(_) => CallingConvention::Aapcs64,
+            Self::MipsCpuContext(_) => CallingConvention::O32,
+            Self::PortableCpuContext(_) => return Err(self.unsupported()),
+        })
+    }
+
+    ///Name of the register holding the return value.
+    fn return_register(&self) -> Result<&'static str, Error> {
+        Ok(match self {
+            Self::Ia32CpuContext(_) => "eax",
+            Self::X64CpuContext(_) => "rax",
+            Self::ArmCpuContext(_) => "r0",
+            Self::Arm64CpuContext(_) => "x0",
+            Self::MipsCpuContext(_) => "v0",
+            Self::PortableCpuContext(_) => return Err(self.unsupported()),
+        })
+    }
+
+    ///Get argument `n` of the current function call.
+    ///
+    ///This follows the calling convention of the platform: System V or
+    ///Windows on x64, cdecl on ia32, AAPCS on arm and arm64 and o32 on mips.
+    ///Arguments passed on the stack are read relative to the stack pointer
+    ///at the entry of the function, so this is only accurate in `on_enter`
+    ///or in a probe at the start of the function. Floating point and
+    ///variadic arguments are not supported.
+    ///
+    ///`n` counts pointer-sized slots rather than C arguments, so values
+    ///wider than a pointer take more than one slot:
+    ///
+    ///* On arm and mips, a 64-bit argument is passed in an even-numbered pair
+    ///  of registers or an 8-byte aligned stack slot. A slot is skipped for
+    ///  alignment when needed, so `f(int a, long long b)` has `b` in slots 2
+    ///  and 3.
+    ///* On Darwin arm64, arguments passed on the stack are packed by their
+    ///  natural size instead of using 8-byte slots. Stack arguments are only
+    ///  located correctly if every stack argument before them is 8 bytes
+    ///  wide.
+    pub fn arg(&self, n: usize) -> Result<NativePointer, Error> {
+        match self.calling_convention()?.arg_location(n) {
+            ArgLocation::Register(r) => Ok(self.register(r).unwrap()),
+            ArgLocation::Stack(offset) => (&self.stack_pointer() + offset).read_pointer(),
+        }
+    }
+
+    ///Set argument `n` of the current function call.
+    ///
+    ///This follows the same conventions as [`arg`](CpuContext::arg).
+    pub fn set_arg(&mut self, n: usize, value: NativePointer) -> Result<(), Error> {
+        match self.calling_convention()?.arg_location(n) {
+            ArgLocation::Register(r) => {
+                self.set_register(r, value);
+                Ok(())
+            }
+            ArgLocation::Stack(offset) => (&self.stack_pointer() + offset).write_pointer(&value),
+        }
+    }
+
+    ///Get the return value of the current function call.
+    ///
+    ///This is only meaningful in `on_leave`.
+    pub fn return_value(&self) -> Result<NativePointer, Error> {
+        Ok(self.register(self.return_register()?).unwrap())
+    }
+
+    ///Set the return value of the current function call.
+    ///
+    ///This is only meaningful in `on_leave`.
+    pub fn set_return_value(&mut self, value: NativePointer) -> Result<(), Error> {
+        let r = self.return_register()?;
+        self.set_register(r, value);
+        Ok(())
+    }
+
+    ///Get the return address of the current function call.
+    ///
+    ///On ia32 and x64 the return address is read from the top of the stack,
+    ///so this is only accurate at the entry of the function.
+    pub fn return_address(&self) -> Result<NativePointer, Error> {
+        match self {
+            Self::Ia32CpuContext(c) => c.esp.read_pointer(),
+            Self::X64CpuContext(c) => c.rsp.read_pointer(),
+            Self::ArmCpuContext(c) => Ok(c.lr.clone()),
+            Self::Arm64CpuContext(c) => Ok(c.lr.clone()),
+            Self::MipsCpuContext(c) => Ok(c.ra.clone()),
+            Self::PortableCpuContext(_) => Err(self.unsupported()),
+        }
+    }
+
+    ///Get the stack pointer.
+    pub fn stack_pointer(&self) -> NativePointer {
+        match self {
+            Self::Ia32CpuContext(c) => c.sp.clone(),
+            Self::X64CpuContext(c) => c.sp.clone(),
+            Self::ArmCpuContext(c) => c.sp.clone(),
+            Self::Arm64CpuContext(c) => c.sp.clone(),
+            Self::MipsCpuContext(c) => c.sp.clone(),
+            Self::PortableCpuContext(c) => c.sp.clone(),
+        }
+    }
+
+    ///Get the frame pointer.
+    ///
+    ///On arm this is `r7` on Darwin, which uses Thumb frame pointers, and
+    ///`r11` elsewhere.
+    pub fn frame_pointer(&self) -> Result<NativePointer, Error> {
+        match self {
+            Self::Ia32CpuContext(c) => Ok(c.ebp.clone()),
+            Self::X64CpuContext(c) => Ok(c.rbp.clone()),
+            Self::ArmCpuContext(c) if crate::process::get_platform() == "darwin" => {
+                Ok(c.r7.clone())
+            }
+            Self::ArmCpuContext(c) => Ok(c.r11.clone()),
+            Self::Arm64CpuContext(c) => Ok(c.fp.clone()),
+            Self::MipsCpuContext(c) => Ok(c.fp.clone()),
+            Self::PortableCpuContext(_) => Err(self.unsupported()),
+        }
+    }
+
+    ///Get the register named `name`, such as `"rax"` or `"x0"`.
+    pub fn register(&self, name: &str) -> Option<NativePointer> {
+        match self {
+            Self::Ia32CpuContext(c) => c.register(name),
+            Self::X64CpuContext(c) => c.register(name),
+            Self::ArmCpuContext(c) => c.register(name),
+            Self::Arm64CpuContext(c) => c.register(name),
+            Self::MipsCpuContext(c) => c.register(name),
+            Self::PortableCpuContext(c) => c.register(name),
+        }
+    }
+
+    ///Set the register named `name`, returning `false` if there is no such
+    ///register.
+    pub fn set_register(&mut self, name: &str, value: NativePointer) -> bool {
+        match self {
+            Self::Ia32CpuContext(c) => c.set_register(name, value),
+            Self::X64CpuContext(c) => c.set_register(name, value),
+            Self::ArmCpuContext(c) => c.set_register(name, value),
+            Self::Arm64CpuContext(c) => c.set_register(name, value),
+            Self::MipsCpuContext(c) => c.set_register(name, value),
+            Self::PortableCpuContext(c) => c.set_register(name, value),
+        }
+    }
+
+    ///Write the registers that were modified through the public fields of
+    ///the contained context back to the CPU context.
+    pub fn commit(&mut self) {
+        match self {
+            Self::Ia32CpuContext(c) => c.commit(),
+            Self::X64CpuContext(c) => c.commit(),
+            Self::ArmCpuContext(c) => c.commit(),
+            Self::Arm64CpuContext(c) => c.commit(),
+            Self::MipsCpuContext(c) => c.commit(),
+            Self::PortableCpuContext(c) => c.commit(),
+        }
+    }
+}
+
 impl FromSys<cpu::CpuContext> for CpuContext {
     fn from_sys(m: cpu::CpuContext) -> Self {
         match crate::process::get_arch() {
@@ -43,14 +286,15 @@ impl FromSys<cpu::CpuContext> for CpuContext {
     }
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct PortableCpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct Ia32CpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
@@ -63,10 +307,11 @@ pub struct Ia32CpuContext {
     pub esi: NativePointer,
     pub edi: NativePointer,
     pub eip: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct X64CpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
@@ -79,6 +324,7 @@ pub struct X64CpuContext {
     pub rsi: NativePointer,
     pub rdi: NativePointer,
     pub r8: NativePointer,
+    pub r9: NativePointer,
     pub r10: NativePointer,
     pub r11: NativePointer,
     pub r12: NativePointer,
@@ -86,10 +332,11 @@ pub struct X64CpuContext {
     pub r14: NativePointer,
     pub r15: NativePointer,
     pub rip: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct ArmCpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
@@ -107,10 +354,11 @@ pub struct ArmCpuContext {
     pub r11: NativePointer,
     pub r12: NativePointer,
     pub lr: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct Arm64CpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
@@ -145,10 +393,11 @@ pub struct Arm64CpuContext {
     pub x28: NativePointer,
     pub fp: NativePointer,
     pub lr: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
 
-#[derive(Debug, DeriveCpu)]
+#[derive(Debug, DeriveCpu, Serialize)]
 pub struct MipsCpuContext {
     pub pc: NativePointer,
     pub sp: NativePointer,
@@ -184,5 +433,73 @@ pub struct MipsCpuContext {
     pub s7: NativePointer,
     pub k0: NativePointer,
     pub k1: NativePointer,
+    #[serde(skip)]
     sys: cpu::CpuContext,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arg_location_registers_then_stack() {
+        assert_eq!(
+            ArgLocation::new(&["a", "b"], 1, 8, 8),
+            ArgLocation::Register("b")
+        );
+        assert_eq!(
+            ArgLocation::new(&["a", "b"], 2, 8, 8),
+            ArgLocation::Stack(8)
+        );
+        assert_eq!(
+            ArgLocation::new(&["a", "b"], 4, 8, 8),
+            ArgLocation::Stack(0x18)
+        );
+    }
+
+    #[test]
+    fn sysv() {
+        let cc = CallingConvention::SysV;
+        assert_eq!(cc.arg_location(0), ArgLocation::Register("rdi"));
+        assert_eq!(cc.arg_location(5), ArgLocation::Register("r9"));
+        assert_eq!(cc.arg_location(6), ArgLocation::Stack(8));
+        assert_eq!(cc.arg_location(7), ArgLocation::Stack(0x10));
+    }
+
+    #[test]
+    fn win64() {
+        let cc = CallingConvention::Win64;
+        assert_eq!(cc.arg_location(0), ArgLocation::Register("rcx"));
+        assert_eq!(cc.arg_location(3), ArgLocation::Register("r9"));
+        assert_eq!(cc.arg_location(4), ArgLocation::Stack(0x28));
+        assert_eq!(cc.arg_location(5), ArgLocation::Stack(0x30));
+    }
+
+    #[test]
+    fn cdecl() {
+        let cc = CallingConvention::Cdecl;
+        assert_eq!(cc.arg_location(0), ArgLocation::Stack(4));
+        assert_eq!(cc.arg_location(2), ArgLocation::Stack(0xc));
+    }
+
+    #[test]
+    fn aapcs() {
+        let cc = CallingConvention::Aapcs32;
+        assert_eq!(cc.arg_location(3), ArgLocation::Register("r3"));
+        assert_eq!(cc.arg_location(4), ArgLocation::Stack(0));
+        assert_eq!(cc.arg_location(5), ArgLocation::Stack(4));
+
+        let cc = CallingConvention::Aapcs64;
+        assert_eq!(cc.arg_location(7), ArgLocation::Register("x7"));
+        assert_eq!(cc.arg_location(8), ArgLocation::Stack(0));
+        assert_eq!(cc.arg_location(9), ArgLocation::Stack(8));
+    }
+
+    #[test]
+    fn o32() {
+        let cc = CallingConvention::O32;
+        assert_eq!(cc.arg_location(3), ArgLocation::Register("a3"));
+        assert_eq!(cc.arg_location(4), ArgLocation::Stack(16));
+        assert_eq!(cc.arg_location(5), ArgLocation::Stack(20));
+    }
+}
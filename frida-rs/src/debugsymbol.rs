@@ -6,8 +6,10 @@
 
 use crate::fromsys::FromSys;
 use crate::nativepointer::NativePointer;
+use serde_derive::Serialize;
 use std::fmt;
 
+#[derive(Serialize)]
 pub struct DebugSymbol {
     ///Address that this symbol is for.
     pub address: NativePointer,
@@ -24,6 +26,7 @@ pub struct DebugSymbol {
     ///Line number in `file_name`
     pub line_number: Option<u32>,
 
+    #[serde(skip)]
     sys: frida_rs_sys::debugsymbol::DebugSymbol,
 }
 
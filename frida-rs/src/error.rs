@@ -0,0 +1,119 @@
+use crate::memory::{Operation, ResolveError};
+use crate::nativepointer::NativePointer;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use wasm_bindgen::JsValue;
+
+///Errors returned by fallible Frida functions.
+#[derive(Debug, Clone)]
+pub enum Error {
+    ///The instrumented process raised an access violation while performing
+    ///`operation` on `address`.
+    AccessViolation {
+        address: NativePointer,
+        operation: Operation,
+    },
+    ///Code cannot be modified because the code signing policy of the
+    ///instrumented process is `"required"`.
+    CodeSigningRequired,
+    ///A memory scanning pattern is malformed.
+    InvalidPattern(String),
+    ///A sequence written to memory does not have the number of elements
+    ///the target expects.
+    LengthMismatch { expected: usize, actual: usize },
+    ///The JavaScript API threw an exception with the given message.
+    JavaScript(String),
+    ///A pointer-chain expression passed to
+    ///[`memory::resolve`](crate::memory::resolve) could not be resolved.
+    Resolve(ResolveError),
+    ///The operation is not supported on the architecture of the instrumented
+    ///process.
+    UnsupportedArchitecture(String),
+}
+
+impl Error {
+    ///Convert an exception thrown by a Frida memory accessor into an
+    ///[`Error`](Error).
+    ///
+    ///Frida attaches a `memory` object describing the faulting access to
+    ///native exceptions. `address` and `operation` are used when the
+    ///exception does not carry those details.
+    pub(crate) fn from_memory_access(
+        e: JsValue,
+        address: &NativePointer,
+        operation: Operation,
+    ) -> Self {
+        let memory = js_sys::Reflect::get(&e, &JsValue::from_str("memory")).unwrap_or_default();
+
+        if memory.is_object() {
+            let address = js_sys::Reflect::get(&memory, &JsValue::from_str("address"))
+                .map(NativePointer::from_jsvalue)
+                .unwrap_or_else(|_| address.clone());
+            let operation = js_sys::Reflect::get(&memory, &JsValue::from_str("operation"))
+                .ok()
+                .and_then(|x| x.as_string())
+                .and_then(|x| Operation::from_str(&x).ok())
+                .unwrap_or(operation);
+
+            return Error::AccessViolation { address, operation };
+        }
+
+        let message = Self::message(&e);
+        if message.contains("access violation") {
+            return Error::AccessViolation {
+                address: address.clone(),
+                operation,
+            };
+        }
+
+        Error::JavaScript(message)
+    }
+
+    fn message(e: &JsValue) -> String {
+        js_sys::Reflect::get(e, &JsValue::from_str("message"))
+            .ok()
+            .and_then(|x| x.as_string())
+            .or_else(|| e.as_string())
+            .unwrap_or_else(|| format!("{:?}", e))
+    }
+}
+
+impl From<JsValue> for Error {
+    fn from(e: JsValue) -> Self {
+        Error::JavaScript(Self::message(&e))
+    }
+}
+
+// Allows conversions that cannot fail, such as from a `Pattern` to itself, to
+// be used where a fallible conversion is expected.
+impl From<Infallible> for Error {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AccessViolation { address, operation } => {
+                write!(f, "access violation during {} of {}", operation, address)
+            }
+            Self::CodeSigningRequired => write!(
+                f,
+                "code signing policy of the instrumented process does not allow modifying code"
+            ),
+            Self::InvalidPattern(message) => write!(f, "invalid pattern: {}", message),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} elements, found {}", expected, actual)
+            }
+            Self::JavaScript(message) => write!(f, "{}", message),
+            Self::Resolve(e) => write!(f, "cannot resolve expression: {}", e),
+            Self::UnsupportedArchitecture(arch) => {
+                write!(f, "operation is not supported on architecture '{}'", arch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
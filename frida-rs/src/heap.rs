@@ -0,0 +1,142 @@
+//!Queries over the allocations of the system heap.
+//!
+//!The functions in this module are built on top of
+//![`process::enumerate_malloc_ranges`](crate::process::enumerate_malloc_ranges)
+//!and therefore only see the allocations known to the system heap.
+
+use crate::memory;
+use crate::nativepointer::NativePointer;
+use crate::process;
+use crate::range::RangeDetails;
+use std::convert::TryInto;
+
+///Pointer-sized value found by
+///[`find_references`](crate::heap::find_references).
+pub struct Reference {
+    ///Address of the pointer-sized value.
+    pub address: NativePointer,
+    ///The pointer-sized value.
+    pub value: NativePointer,
+    ///Allocation containing the value.
+    pub allocation: RangeDetails,
+}
+
+thread_local! {
+    static BIG_ENDIAN: bool = probe_big_endian();
+}
+
+///Find the byte order by writing a known word to memory.
+fn probe_big_endian() -> bool {
+    memory::alloc(4)
+        .and_then(|a| {
+            a.write_u32(0x0102_0304)?;
+            a.read_u8()
+        })
+        .map(|b| b == 0x01)
+        // Most mips targets supported by Frida are big-endian.
+        .unwrap_or(true)
+}
+
+///Check if the instrumented process is big-endian.
+///
+///Frida reports both byte orders of mips as `"mips"`, so the byte order is
+///probed once on that architecture. The other architectures supported by
+///Frida are little-endian.
+fn is_big_endian() -> bool {
+    process::get_arch() == "mips" && BIG_ENDIAN.with(|b| *b)
+}
+
+///Decode a pointer-sized `word` read from memory.
+fn decode_word(word: &[u8], big_endian: bool) -> u64 {
+    match (word.len(), big_endian) {
+        (4, false) => u32::from_le_bytes(word.try_into().unwrap()) as u64,
+        (4, true) => u32::from_be_bytes(word.try_into().unwrap()) as u64,
+        (_, false) => u64::from_le_bytes(word.try_into().unwrap()),
+        (_, true) => u64::from_be_bytes(word.try_into().unwrap()),
+    }
+}
+
+///Scan every live allocation for pointer-sized values in `[start, end)`.
+///
+///Values are read at offsets aligned to the pointer size from the start of
+///each allocation. Allocations that can no longer be read are skipped.
+fn scan_allocations(start: u64, end: u64) -> Vec<Reference> {
+    let pointer_size = process::get_pointer_size();
+    let big_endian = is_big_endian();
+    let mut references = Vec::new();
+
+    for allocation in process::enumerate_malloc_ranges() {
+        let bytes = match allocation.base.read_byte_array(allocation.size) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+
+        for (i, word) in bytes.chunks_exact(pointer_size).enumerate() {
+            let value = decode_word(word, big_endian);
+
+            if value >= start && value < end {
+                references.push(Reference {
+                    address: &allocation.base + i * pointer_size,
+                    value: NativePointer::from(value),
+                    allocation: allocation.clone(),
+                });
+            }
+        }
+    }
+
+    references
+}
+
+///Find the pointer-sized values on the heap that are equal to `target`.
+///
+///This reads every live allocation once and decodes the values using the
+///byte order of the instrumented process.
+///
+///```
+///for r in heap::find_references(&object) {
+///    console_log!("{} is referenced from {}", object, r.address);
+///}
+///```
+pub fn find_references(target: &NativePointer) -> Vec<Reference> {
+    let start = target.to_u64();
+    scan_allocations(start, start.saturating_add(1))
+}
+
+///Find the pointer-sized values on the heap that point within the `size`
+///bytes starting at `target`.
+///
+///This also finds interior pointers, such as pointers to a field of an
+///object.
+pub fn find_references_within(target: &NativePointer, size: usize) -> Vec<Reference> {
+    let start = target.to_u64();
+    scan_allocations(start, start.saturating_add(size as u64))
+}
+
+///Get the allocation containing `address`.
+pub fn find_allocation(address: &NativePointer) -> Option<RangeDetails> {
+    let address = address.to_u64();
+
+    process::enumerate_malloc_ranges().into_iter().find(|r| {
+        let base = r.base.to_u64();
+        address >= base && address < base + r.size as u64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_word_byte_order() {
+        assert_eq!(decode_word(&[0x04, 0x03, 0x02, 0x01], false), 0x0102_0304);
+        assert_eq!(decode_word(&[0x01, 0x02, 0x03, 0x04], true), 0x0102_0304);
+        assert_eq!(
+            decode_word(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01], false),
+            0x0102_0304_0506_0708
+        );
+        assert_eq!(
+            decode_word(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08], true),
+            0x0102_0304_0506_0708
+        );
+    }
+}
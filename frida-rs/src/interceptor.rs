@@ -4,15 +4,28 @@
 //!grouped under
 //![https://frida.re/docs/javascript-api/#interceptor](https://frida.re/docs/javascript-api/#interceptor)
 
+mod state;
+mod typed;
+
+use crate::error::Error;
 use crate::fromsys::FromSys;
+use crate::memory::Allocation;
+use crate::nativefunction::{NativeArgs, NativeCallback, NativeFunction, NativeType, RawCallback};
 use crate::nativepointer::NativePointer;
 use frida_rs_sys::interceptor;
 use frida_rs_sys::utils::this_wrap;
 use js_sys::Object;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
-pub use frida_rs_sys::interceptor::{InvocationArgs, InvocationReturnValue};
+pub use frida_rs_derive::HookArgs;
+pub use frida_rs_sys::interceptor::InvocationReturnValue;
+pub use typed::{
+    attach_typed, CStrPtr, FromArg, HookArgs, Ptr, TypedInvocationCallbacks, Utf16Ptr,
+};
 
 ///Per-invocation object that contains useful properties about the program at
 ///the point of interception.
@@ -23,20 +36,28 @@ pub use frida_rs_sys::interceptor::{InvocationArgs, InvocationReturnValue};
 ///The [`get`][get] and [`set`][set] methods can be used to store arbitrary
 ///Rust objects that will be accessible at any point during the invocation.
 ///For example, this could be used to store values during `on_enter` that can
-///later be retrieved during `on_leave`.
+///later be retrieved during `on_leave`. [`state`][state] does the same without
+///serializing the objects.
 ///
 ///[get]: InvocationContext::get
 ///[set]: InvocationContext::set
+///[state]: InvocationContext::state
 #[derive(Debug)]
 pub struct InvocationContext {
     ///Return address of the intercepted function.
     pub return_address: NativePointer,
     ///Current CPU context.
+    ///
+    ///The context is backed by the registers of the intercepted thread and
+    ///is only writable while the callback runs. Registers written back to it,
+    ///as described in the [`cpu`](crate::cpu) module, take effect once the
+    ///callback returns.
     pub context: crate::cpu::CpuContext,
     ///OS thread ID.
     pub thread_id: u32,
     ///Call depth relative to other invocations.
     pub depth: u32,
+    listener: u64,
     _js: interceptor::InvocationContext,
 }
 
@@ -57,6 +78,29 @@ impl InvocationContext {
     {
         self._js.set(prop, JsValue::from_serde(val).unwrap())
     }
+
+    ///Get the state of type `T` for this invocation.
+    ///
+    ///The state is created with `T::default()` the first time it is
+    ///requested and is shared by the `on_enter` and `on_leave` callbacks of
+    ///the same invocation. It is kept on the Rust side, so `T` does not need
+    ///to be serializable, and is dropped after `on_leave` returns, or after
+    ///`on_enter` returns if there is no `on_leave` callback.
+    ///
+    ///```
+    ///// on_enter
+    ///this.state::<Vec<NativePointer>>().borrow_mut().push(args.get(0));
+    ///
+    ///// on_leave
+    ///let buffers = this.state::<Vec<NativePointer>>();
+    ///```
+    pub fn state<T: Default + 'static>(&self) -> Rc<RefCell<T>> {
+        state::get(self.state_key())
+    }
+
+    fn state_key(&self) -> state::Key {
+        (self.listener, self.thread_id, self.depth)
+    }
 }
 
 impl From<interceptor::InvocationContext> for InvocationContext {
@@ -66,6 +110,7 @@ impl From<interceptor::InvocationContext> for InvocationContext {
             context: crate::cpu::CpuContext::from_sys(m.context()),
             thread_id: m.thread_id(),
             depth: m.depth(),
+            listener: 0,
             _js: m,
         }
     }
@@ -88,13 +133,234 @@ impl wasm_bindgen::convert::FromWasmAbi for InvocationContext {
     }
 }
 
+///Arguments to an invocation of the intercepted function.
+///
+///Use the [`get`][get] and [`set`][set] methods to access the arguments by
+///index. Frida makes no guarantees about the validty of the index and it is
+///possible to index into more arguments than the intercepted function
+///accepts.
+///
+///[get]: InvocationArgs::get
+///[set]: InvocationArgs::set
+#[derive(Debug)]
+pub struct InvocationArgs(interceptor::InvocationArgs);
+
+///Value that can be written to an argument with
+///[`InvocationArgs::set`](crate::interceptor::InvocationArgs::set).
+///
+///This is created from a [`NativePointer`](crate::NativePointer), an integer
+///or an [`Allocation`](crate::memory::Allocation).
+pub struct ArgValue {
+    pointer: NativePointer,
+    allocation: bool,
+}
+
+impl From<NativePointer> for ArgValue {
+    fn from(pointer: NativePointer) -> Self {
+        ArgValue {
+            pointer,
+            allocation: false,
+        }
+    }
+}
+
+impl From<&NativePointer> for ArgValue {
+    fn from(pointer: &NativePointer) -> Self {
+        ArgValue::from(pointer.clone())
+    }
+}
+
+impl From<Allocation> for ArgValue {
+    fn from(allocation: Allocation) -> Self {
+        ArgValue::from(&allocation)
+    }
+}
+
+impl From<&Allocation> for ArgValue {
+    fn from(allocation: &Allocation) -> Self {
+        ArgValue {
+            pointer: (**allocation).clone(),
+            allocation: true,
+        }
+    }
+}
+
+macro_rules! impl_arg_value_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for ArgValue {
+                fn from(value: $t) -> Self {
+                    ArgValue::from(NativePointer::from(value as u64))
+                }
+            }
+        )*
+    };
+}
+
+// Signed integers are sign-extended to 64 bits.
+impl_arg_value_from_int!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+thread_local! {
+    // Allocations passed to `InvocationArgs::set` during the current
+    // `on_enter` callback.
+    static PENDING_ALLOCATIONS: RefCell<Vec<JsValue>> = const { RefCell::new(Vec::new()) };
+}
+
+///Property of the JavaScript `this` object keeping the allocations passed to
+///`InvocationArgs::set` alive until the invocation returns.
+const ALLOCATIONS_PROPERTY: &str = "_fridaRsAllocations";
+
+///Allocations kept alive by a listener without an `on_leave` callback.
+type Retained = Rc<RefCell<Vec<JsValue>>>;
+
+impl InvocationArgs {
+    ///Get the argument at `index`.
+    pub fn get(&self, index: u32) -> NativePointer {
+        NativePointer::from_sys(self.0.get(index))
+    }
+
+    ///Replace the argument at `index` with `value`.
+    ///
+    ///When `value` is an [`Allocation`](crate::memory::Allocation), the
+    ///allocated memory stays valid until the intercepted function returns,
+    ///even if the `Allocation` itself is dropped. This makes it safe to
+    ///replace a string argument:
+    ///
+    ///```
+    ///args.set(0, memory::alloc_utf8_string("/tmp/other")?);
+    ///```
+    ///
+    ///Without an `on_leave` callback, as with
+    ///[`attach_probe`](crate::interceptor::attach_probe), there is no way to
+    ///tell when the function returns. The allocation is then kept alive until
+    ///the listener is detached with
+    ///[`InvocationListener::detach`](crate::interceptor::InvocationListener::detach),
+    ///so hooks that are called often should replace arguments from a hook
+    ///with an `on_leave` callback instead.
+    ///
+    ///Outside of the `on_enter` callback of
+    ///[`attach`](crate::interceptor::attach) or
+    ///[`attach_probe`](crate::interceptor::attach_probe), the allocation is
+    ///only kept alive by the `Allocation` itself.
+    pub fn set<T: Into<ArgValue>>(&self, index: u32, value: T) {
+        let value = value.into();
+        let a: &js_sys::Array = self.0.unchecked_ref();
+        a.set(index, value.pointer.to_sys().clone().into());
+
+        if value.allocation {
+            PENDING_ALLOCATIONS
+                .with(|p| p.borrow_mut().push(value.pointer.to_sys().clone().into()));
+        }
+    }
+}
+
+///Drop allocations passed to `InvocationArgs::set` outside of an `on_enter`
+///callback, so that they are not attributed to the next invocation.
+fn discard_allocations() {
+    PENDING_ALLOCATIONS.with(|p| p.borrow_mut().clear());
+}
+
+///Move the allocations passed to `InvocationArgs::set` onto the JavaScript
+///`this` object of the current invocation, or into `retained` if the
+///invocation has no `on_leave` callback.
+fn keep_allocations_alive(context: &JsValue, retained: Option<&Retained>) {
+    let pending = PENDING_ALLOCATIONS.with(|p| p.replace(Vec::new()));
+    if pending.is_empty() {
+        return;
+    }
+
+    if let Some(retained) = retained {
+        retained.borrow_mut().extend(pending);
+        return;
+    }
+
+    let allocations = js_sys::Array::new();
+    for a in pending {
+        allocations.push(&a);
+    }
+
+    js_sys::Reflect::set(
+        context,
+        &JsValue::from_str(ALLOCATIONS_PROPERTY),
+        &allocations,
+    )
+    .unwrap();
+}
+
+// TODO: This feels like a bad hack... see if there is a better way...
+impl wasm_bindgen::describe::WasmDescribe for InvocationArgs {
+    fn describe() {
+        interceptor::InvocationArgs::describe()
+    }
+}
+
+// TODO: This feels like a bad hack... see if there is a better way...
+impl wasm_bindgen::convert::FromWasmAbi for InvocationArgs {
+    type Abi = u32;
+
+    unsafe fn from_abi(js: u32) -> InvocationArgs {
+        InvocationArgs(interceptor::InvocationArgs::from_abi(js))
+    }
+}
+
 pub struct InvocationCallbacks {
     pub on_enter: Option<Box<dyn FnMut(InvocationContext, InvocationArgs)>>,
     pub on_leave: Option<Box<dyn FnMut(InvocationContext, InvocationReturnValue)>>,
 }
 
+type OnEnter = Closure<dyn FnMut(InvocationContext, InvocationArgs)>;
+type OnLeave = Closure<dyn FnMut(InvocationContext, InvocationReturnValue)>;
+
+///Handle to the callbacks installed by [`attach`](crate::interceptor::attach).
+///
+///Dropping the listener leaves the callbacks installed for the lifetime of
+///the script. Use [`detach`](InvocationListener::detach) to remove them.
+pub struct InvocationListener {
+    listener: interceptor::InvocationListener,
+    on_enter: Option<OnEnter>,
+    on_leave: Option<OnLeave>,
+}
+
+impl InvocationListener {
+    ///Detach the callbacks and free them.
+    ///
+    ///The callbacks are only freed after
+    ///[`flush`](crate::interceptor::flush) has returned, so no thread is still
+    ///running them. This also frees the callbacks of a listener that was
+    ///already removed by [`detach_all`](crate::interceptor::detach_all).
+    ///
+    ///This is equivalent to calling `InvocationListener.detach` in the
+    ///JavaScript API.
+    pub fn detach(mut self) {
+        self.listener.detach();
+        interceptor::flush();
+
+        self.on_enter.take();
+        self.on_leave.take();
+    }
+}
+
+impl Drop for InvocationListener {
+    fn drop(&mut self) {
+        // The callbacks may still be called, so they are never freed unless
+        // the listener is detached.
+        if let Some(c) = self.on_enter.take() {
+            c.forget();
+        }
+
+        if let Some(c) = self.on_leave.take() {
+            c.forget();
+        }
+    }
+}
+
 ///Intercept calls to `target`.
 ///
+///The callbacks stay installed until
+///[`InvocationListener::detach`](crate::interceptor::InvocationListener::detach)
+///or [`detach_all`](crate::interceptor::detach_all) is called. Dropping the
+///returned listener does not remove them.
+///
 ///This is equivalent to calling `Interceptor.attach` in the JavaScript API.
 ///
 ///```
@@ -107,13 +373,40 @@ pub struct InvocationCallbacks {
 ///    }))
 ///};
 ///
-///Interceptor::attach(target, callbacks);
+///let listener = interceptor::attach(target, callbacks);
 ///```
-pub fn attach(target: NativePointer, callbacks: InvocationCallbacks) {
+pub fn attach(target: NativePointer, callbacks: InvocationCallbacks) -> InvocationListener {
     let callbacks_object = Object::new();
+    let listener = state::next_listener_id();
+
+    // The `this` object only lives until the function returns if there is an
+    // `onLeave` callback. Without one, the state of an invocation is dropped
+    // after `on_enter` and the allocations are kept by the listener instead.
+    let retained: Option<Retained> = match callbacks.on_leave {
+        Some(_) => None,
+        None => Some(Rc::new(RefCell::new(Vec::new()))),
+    };
+
+    let on_enter = callbacks.on_enter.map(|mut f| {
+        let on_enter: OnEnter = Closure::wrap(Box::new(
+            move |mut context: InvocationContext, args: InvocationArgs| {
+                context.listener = listener;
+                let key = context.state_key();
+                // Drop any state left behind by an invocation at the same
+                // depth that never returned.
+                state::clear(key);
+                discard_allocations();
+
+                let js: &JsValue = context._js.as_ref();
+                let js = js.clone();
+                f(context, args);
+                keep_allocations_alive(&js, retained.as_ref());
 
-    if let Some(f) = callbacks.on_enter {
-        let on_enter = Closure::wrap(f);
+                if retained.is_some() {
+                    state::clear(key);
+                }
+            },
+        ));
         let on_enter_wrapped = this_wrap(on_enter.as_ref().unchecked_ref());
         js_sys::Reflect::set(
             &callbacks_object,
@@ -121,11 +414,20 @@ pub fn attach(target: NativePointer, callbacks: InvocationCallbacks) {
             &on_enter_wrapped,
         )
         .unwrap();
-        on_enter.forget();
-    }
+        on_enter
+    });
 
-    if let Some(f) = callbacks.on_leave {
-        let on_leave = Closure::wrap(f);
+    let on_leave = callbacks.on_leave.map(|mut f| {
+        let on_leave: OnLeave = Closure::wrap(Box::new(
+            move |mut context: InvocationContext, retval: InvocationReturnValue| {
+                context.listener = listener;
+                let key = context.state_key();
+                discard_allocations();
+                f(context, retval);
+                discard_allocations();
+                state::clear(key);
+            },
+        ));
         let on_leave_wrapped = this_wrap(on_leave.as_ref().unchecked_ref());
         js_sys::Reflect::set(
             &callbacks_object,
@@ -133,8 +435,128 @@ pub fn attach(target: NativePointer, callbacks: InvocationCallbacks) {
             &on_leave_wrapped,
         )
         .unwrap();
-        on_leave.forget();
+        on_leave
+    });
+
+    InvocationListener {
+        listener: interceptor::attach(&target.into_sys(), callbacks_object),
+        on_enter,
+        on_leave,
     }
+}
 
-    interceptor::attach(&target.into_sys(), callbacks_object);
+///Call `callback` whenever the instruction at `target` is executed.
+///
+///Unlike [`attach`](crate::interceptor::attach), `target` does not need to be
+///the start of a function. The registers can be modified through the writable
+///[`context`](InvocationContext::context) of `callback`, for example to change
+///a value in the middle of a function. The state returned by
+///[`InvocationContext::state`](InvocationContext::state) is dropped once
+///`callback` returns, and allocations passed to
+///[`InvocationArgs::set`](InvocationArgs::set) are kept alive until the
+///listener is detached.
+///
+///This is equivalent to calling `Interceptor.attach` with a function instead
+///of a callbacks object in the JavaScript API.
+///
+///```
+///let listener = interceptor::attach_probe(target, Box::new(|mut this: InvocationContext, _args| {
+///    if let CpuContext::X64CpuContext(c) = &mut this.context {
+///        c.set_rax(NativePointer::from(0));
+///    }
+///}));
+///```
+pub fn attach_probe(
+    target: NativePointer,
+    mut callback: Box<dyn FnMut(InvocationContext, InvocationArgs)>,
+) -> InvocationListener {
+    let listener = state::next_listener_id();
+    let retained: Retained = Rc::new(RefCell::new(Vec::new()));
+
+    let on_enter: OnEnter = Closure::wrap(Box::new(
+        move |mut context: InvocationContext, args: InvocationArgs| {
+            context.listener = listener;
+            let key = context.state_key();
+            discard_allocations();
+
+            let js: &JsValue = context._js.as_ref();
+            let js = js.clone();
+            callback(context, args);
+            keep_allocations_alive(&js, Some(&retained));
+
+            state::clear(key);
+        },
+    ));
+    let on_enter_wrapped = this_wrap(on_enter.as_ref().unchecked_ref());
+
+    InvocationListener {
+        listener: interceptor::attach_probe(&target.into_sys(), &on_enter_wrapped),
+        on_enter: Some(on_enter),
+        on_leave: None,
+    }
+}
+
+thread_local! {
+    // Replacements installed by `replace`, kept alive until `revert`.
+    static REPLACEMENTS: RefCell<HashMap<u64, RawCallback>> = RefCell::new(HashMap::new());
+}
+
+///Replace the function at `target` with `replacement`.
+///
+///The returned [`NativeFunction`](crate::nativefunction::NativeFunction)
+///calls the original implementation of `target`, including when called from
+///within `replacement`. `replacement` is kept alive until
+///[`revert`](crate::interceptor::revert) is called.
+///
+///This is equivalent to calling `Interceptor.replace` in the JavaScript API.
+///
+///```
+///let original = NativeFunction::<(i32,), i32>::new(&target);
+///let o = original.clone();
+///interceptor::replace(&target, NativeCallback::new(move |(x,): (i32,)| {
+///    o.call((x,)).unwrap() + 1
+///}))?;
+///```
+pub fn replace<A, R>(
+    target: &NativePointer,
+    replacement: NativeCallback<A, R>,
+) -> Result<NativeFunction<A, R>, Error>
+where
+    A: NativeArgs,
+    R: NativeType,
+{
+    interceptor::replace(target.to_sys(), replacement.raw.sys.as_ref())?;
+
+    REPLACEMENTS.with(|r| r.borrow_mut().insert(target.to_u64(), replacement.raw));
+
+    Ok(NativeFunction::new(target))
+}
+
+///Revert the function at `target` to its original implementation and free
+///the replacement installed by [`replace`](crate::interceptor::replace).
+///
+///This is equivalent to calling `Interceptor.revert` in the JavaScript API.
+pub fn revert(target: &NativePointer) {
+    interceptor::revert(target.to_sys());
+    interceptor::flush();
+
+    REPLACEMENTS.with(|r| r.borrow_mut().remove(&target.to_u64()));
+}
+
+///Detach all callbacks installed by [`attach`](crate::interceptor::attach).
+///
+///The callbacks of listeners that are still held can then be freed with
+///[`InvocationListener::detach`](crate::interceptor::InvocationListener::detach).
+///The callbacks of listeners that were dropped are never freed.
+///
+///This is equivalent to calling `Interceptor.detachAll` in the JavaScript API.
+pub fn detach_all() {
+    interceptor::detach_all();
+}
+
+///Ensure that pending changes have been committed to memory.
+///
+///This is equivalent to calling `Interceptor.flush` in the JavaScript API.
+pub fn flush() {
+    interceptor::flush();
 }
@@ -0,0 +1,47 @@
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+///Identifies an invocation by listener ID, thread ID and call depth.
+pub(crate) type Key = (u64, u32, u32);
+
+type Slots = HashMap<TypeId, Rc<dyn Any>>;
+
+thread_local! {
+    static STATES: RefCell<HashMap<Key, Slots>> = RefCell::new(HashMap::new());
+    static NEXT_LISTENER: Cell<u64> = const { Cell::new(1) };
+}
+
+///Allocate an ID distinguishing the invocations seen by a listener from the
+///ones seen by other listeners.
+pub(crate) fn next_listener_id() -> u64 {
+    NEXT_LISTENER.with(|n| {
+        let id = n.get();
+        n.set(id + 1);
+        id
+    })
+}
+
+///Get the state of type `T` for the invocation `key`, creating it if needed.
+pub(crate) fn get<T: Default + 'static>(key: Key) -> Rc<RefCell<T>> {
+    STATES.with(|s| {
+        let slot = s
+            .borrow_mut()
+            .entry(key)
+            .or_default()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Rc::new(RefCell::new(T::default())))
+            .clone();
+
+        slot.downcast::<RefCell<T>>().unwrap()
+    })
+}
+
+///Drop all state of the invocation `key`.
+pub(crate) fn clear(key: Key) {
+    // The state is dropped outside of the borrow, in case a destructor
+    // accesses the state of another invocation.
+    let slots = STATES.with(|s| s.borrow_mut().remove(&key));
+    drop(slots);
+}
@@ -0,0 +1,342 @@
+use crate::fromsys::FromSys;
+use crate::interceptor::{
+    attach, InvocationArgs, InvocationCallbacks, InvocationContext, InvocationListener,
+    InvocationReturnValue,
+};
+use crate::memory::FromMemory;
+use crate::nativepointer::NativePointer;
+use crate::process;
+use std::convert::TryFrom;
+
+///Types that can be decoded from an argument or the return value of an
+///intercepted function.
+pub trait FromArg: Sized {
+    fn from_arg(arg: NativePointer) -> Self;
+}
+
+impl FromArg for () {
+    fn from_arg(_arg: NativePointer) -> Self {}
+}
+
+impl FromArg for NativePointer {
+    fn from_arg(arg: NativePointer) -> Self {
+        arg
+    }
+}
+
+impl FromArg for bool {
+    fn from_arg(arg: NativePointer) -> Self {
+        arg.to_u32() & 0xff != 0
+    }
+}
+
+macro_rules! impl_from_arg_32 {
+    ($($t:ty),*) => {
+        $(
+            impl FromArg for $t {
+                fn from_arg(arg: NativePointer) -> Self {
+                    arg.to_u32() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg_32!(i8, u8, i16, u16, i32, u32);
+
+macro_rules! impl_from_arg_64 {
+    ($($t:ty),*) => {
+        $(
+            impl FromArg for $t {
+                fn from_arg(arg: NativePointer) -> Self {
+                    arg.to_u64() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg_64!(i64, u64);
+
+///Decodes a pointer-sized argument.
+///
+///# Panics
+///
+///`usize` is 32 bits wide in WebAssembly. Decoding panics if the instrumented
+///process is 64-bit and the argument does not fit, use `u64` or
+///`NativePointer` for arguments that may not.
+impl FromArg for usize {
+    fn from_arg(arg: NativePointer) -> Self {
+        usize_from_arg(arg.to_u64(), process::get_pointer_size())
+    }
+}
+
+///Decodes a pointer-sized signed argument.
+///
+///# Panics
+///
+///`isize` is 32 bits wide in WebAssembly. Decoding panics if the instrumented
+///process is 64-bit and the argument does not fit, use `i64` or
+///`NativePointer` for arguments that may not.
+impl FromArg for isize {
+    fn from_arg(arg: NativePointer) -> Self {
+        isize_from_arg(arg.to_u64(), process::get_pointer_size())
+    }
+}
+
+fn usize_from_arg(value: u64, pointer_size: usize) -> usize {
+    if pointer_size == 4 {
+        return value as u32 as usize;
+    }
+
+    usize::try_from(value).unwrap_or_else(|_| {
+        panic!(
+            "argument {:#x} does not fit in a usize, decode it as a u64 instead",
+            value
+        )
+    })
+}
+
+fn isize_from_arg(value: u64, pointer_size: usize) -> isize {
+    if pointer_size == 4 {
+        return value as u32 as i32 as isize;
+    }
+
+    isize::try_from(value as i64).unwrap_or_else(|_| {
+        panic!(
+            "argument {} does not fit in an isize, decode it as an i64 instead",
+            value as i64
+        )
+    })
+}
+
+///Argument pointing to a NUL-terminated C string.
+///
+///The string is read when the argument is decoded. `value` is `None` if the
+///pointer is NULL or cannot be read.
+#[derive(Debug, Clone)]
+pub struct CStrPtr {
+    pub pointer: NativePointer,
+    pub value: Option<String>,
+}
+
+impl FromArg for CStrPtr {
+    fn from_arg(arg: NativePointer) -> Self {
+        let value = if arg.is_null() {
+            None
+        } else {
+            arg.read_c_string(None).ok().flatten()
+        };
+
+        CStrPtr {
+            pointer: arg,
+            value,
+        }
+    }
+}
+
+///Argument pointing to a NUL-terminated UTF-16 string.
+///
+///The string is read when the argument is decoded. `value` is `None` if the
+///pointer is NULL or cannot be read.
+#[derive(Debug, Clone)]
+pub struct Utf16Ptr {
+    pub pointer: NativePointer,
+    pub value: Option<String>,
+}
+
+impl FromArg for Utf16Ptr {
+    fn from_arg(arg: NativePointer) -> Self {
+        let value = if arg.is_null() {
+            None
+        } else {
+            arg.read_utf16_string(None).ok().flatten()
+        };
+
+        Utf16Ptr {
+            pointer: arg,
+            value,
+        }
+    }
+}
+
+///Argument pointing to a `T`.
+///
+///The `T` is read when the argument is decoded. `value` is `None` if the
+///pointer is NULL or cannot be read.
+#[derive(Debug, Clone)]
+pub struct Ptr<T> {
+    pub pointer: NativePointer,
+    pub value: Option<T>,
+}
+
+impl<T: FromMemory> FromArg for Ptr<T> {
+    fn from_arg(arg: NativePointer) -> Self {
+        let value = if arg.is_null() {
+            None
+        } else {
+            T::from_memory(&arg).ok()
+        };
+
+        Ptr {
+            pointer: arg,
+            value,
+        }
+    }
+}
+
+///Types that can be decoded from the arguments of an intercepted function.
+///
+///This is implemented for tuples of up to 10 [`FromArg`] types and can be
+///derived for structs with `#[derive(HookArgs)]`.
+pub trait HookArgs: Sized {
+    fn from_args(args: &InvocationArgs) -> Self;
+}
+
+macro_rules! impl_hook_args {
+    ($($t:ident $i:tt),*) => {
+        impl<$($t: FromArg),*> HookArgs for ($($t,)*) {
+            #[allow(unused_variables, clippy::unused_unit)]
+            fn from_args(args: &InvocationArgs) -> Self {
+                ($( $t::from_arg(args.get($i)), )*)
+            }
+        }
+    };
+}
+
+impl_hook_args!();
+impl_hook_args!(A 0);
+impl_hook_args!(A 0, B 1);
+impl_hook_args!(A 0, B 1, C 2);
+impl_hook_args!(A 0, B 1, C 2, D 3);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_hook_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+
+///Callbacks for [`attach_typed`](crate::interceptor::attach_typed).
+#[allow(clippy::type_complexity)]
+pub struct TypedInvocationCallbacks<A, R> {
+    pub on_enter: Option<Box<dyn FnMut(InvocationContext, &A)>>,
+    pub on_leave: Option<Box<dyn FnMut(InvocationContext, A, R)>>,
+}
+
+///Arguments decoded in `on_enter`, waiting for `on_leave` in the state of
+///the invocation.
+struct Pending<A>(Option<A>);
+
+impl<A> Default for Pending<A> {
+    fn default() -> Self {
+        Pending(None)
+    }
+}
+
+///Intercept calls to `target`, decoding the arguments into `A` and the return
+///value into `R`.
+///
+///The arguments are decoded when the function is entered. `on_leave` receives
+///them along with the return value, so values read from pointers reflect the
+///state at the time of the call.
+///
+///```
+///#[derive(HookArgs)]
+///struct OpenArgs {
+///    path: CStrPtr,
+///    flags: i32,
+///}
+///
+///let listener = interceptor::attach_typed(target, TypedInvocationCallbacks {
+///    on_enter: None,
+///    on_leave: Some(Box::new(|_, args: OpenArgs, fd: i32| {
+///        console_log!("open({:?}, {}) = {}", args.path.value, args.flags, fd);
+///    })),
+///});
+///```
+pub fn attach_typed<A, R>(
+    target: NativePointer,
+    callbacks: TypedInvocationCallbacks<A, R>,
+) -> InvocationListener
+where
+    A: HookArgs + 'static,
+    R: FromArg + 'static,
+{
+    let keep = callbacks.on_leave.is_some();
+    let on_enter = match (callbacks.on_enter, keep) {
+        (None, false) => None,
+        (mut f, _) => {
+            let on_enter: Box<dyn FnMut(InvocationContext, InvocationArgs)> =
+                Box::new(move |context: InvocationContext, args: InvocationArgs| {
+                    let pending = if keep {
+                        Some(context.state::<Pending<A>>())
+                    } else {
+                        None
+                    };
+                    let args = A::from_args(&args);
+
+                    if let Some(f) = f.as_mut() {
+                        f(context, &args);
+                    }
+
+                    if let Some(pending) = pending {
+                        pending.borrow_mut().0 = Some(args);
+                    }
+                });
+            Some(on_enter)
+        }
+    };
+
+    let on_leave = callbacks.on_leave.map(|mut f| {
+        let on_leave: Box<dyn FnMut(InvocationContext, InvocationReturnValue)> = Box::new(
+            move |context: InvocationContext, retval: InvocationReturnValue| {
+                let args = match context.state::<Pending<A>>().borrow_mut().0.take() {
+                    Some(a) => a,
+                    None => return,
+                };
+
+                let p: &frida_rs_sys::nativepointer::NativePointer = retval.as_ref();
+                f(
+                    context,
+                    args,
+                    R::from_arg(NativePointer::from_sys(p.clone())),
+                );
+            },
+        );
+        on_leave
+    });
+
+    attach(target, InvocationCallbacks { on_enter, on_leave })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_sized_on_32_bit_targets() {
+        assert_eq!(usize_from_arg(0xffff_ffff, 4), 0xffff_ffff);
+        assert_eq!(isize_from_arg(0xffff_ffff, 4), -1);
+        assert_eq!(isize_from_arg(0x7fff_ffff, 4), 0x7fff_ffff);
+    }
+
+    #[test]
+    fn pointer_sized_on_64_bit_targets() {
+        assert_eq!(usize_from_arg(0x1234, 8), 0x1234);
+        assert_eq!(isize_from_arg(-2i64 as u64, 8), -2);
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    #[should_panic(expected = "does not fit in a usize")]
+    fn usize_does_not_truncate() {
+        usize_from_arg(0x1_0000_0000, 8);
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    #[should_panic(expected = "does not fit in an isize")]
+    fn isize_does_not_truncate() {
+        isize_from_arg(-0x1_0000_0000i64 as u64, 8);
+    }
+}
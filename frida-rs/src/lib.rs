@@ -6,6 +6,13 @@
 //!
 //!This crate is still a work-in-progress. The API is not stable and is
 //!subject to breaking changes until the crate reaches 1.0. Use with care.
+
+// Lets the code generated by the derive macros, which refers to
+// `::frida_rs`, be used by the tests of this crate.
+#[cfg(test)]
+extern crate self as frida_rs;
+
+mod error;
 mod fromsys;
 mod nativepointer;
 
@@ -13,14 +20,17 @@ pub mod apiresolver;
 pub mod console;
 pub mod cpu;
 pub mod debugsymbol;
+pub mod heap;
 pub mod interceptor;
 pub mod memory;
 pub mod module;
 pub mod modulemap;
+pub mod nativefunction;
 pub mod process;
 pub mod range;
 pub mod thread;
 
+pub use error::Error;
 pub use frida_rs_sys::frida::ArrayBuffer;
 pub use frida_rs_sys::frida::RecvMessage;
 pub use nativepointer::NativePointer;
@@ -1,30 +1,403 @@
+//!Frida functions for memory-level functionality.
+//!
+//!The functions in this module correspond to the JavaScript functions
+//!grouped under
+//![https://frida.re/docs/javascript-api/#memory](https://frida.re/docs/javascript-api/#memory).
+
+pub mod access_monitor;
+
+mod allocation;
+mod copy;
+mod cursor;
+mod patch;
+mod pattern;
+mod protection;
+mod resolve;
+mod scan;
+mod snapshot;
+
+use crate::error::Error;
 use crate::nativepointer::NativePointer;
+use crate::range::RangeDetails;
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::fmt;
+use std::str;
+
+pub use allocation::{
+    alloc, alloc_ansi_string, alloc_near, alloc_utf16_string, alloc_utf8_string, Allocation,
+};
+pub use copy::{copy, copy_from_slice, copy_to_slice, dup};
+pub use cursor::Cursor;
+pub use frida_rs_derive::{pattern, FromMemory, ToMemory};
+pub use patch::patch_code;
+pub use pattern::Pattern;
+pub use protection::{protect, query_protection, Protection};
+pub use resolve::{resolve, ResolveError, ResolveErrorKind};
+pub use scan::{scan, scan_async, Match, Scan, ScanAction};
+pub use snapshot::{Change, Diff, RangeDiff, Snapshot};
+
+///Kind of memory access. Reported by
+///[`Error::AccessViolation`](crate::Error::AccessViolation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Read,
+    Write,
+    Execute,
+}
+
+impl str::FromStr for Operation {
+    type Err = String;
 
-///Matched values returned by [`scan`](crate::memory::scan).
-pub struct Match {
-    pub address: NativePointer,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Operation::Read),
+            "write" => Ok(Operation::Write),
+            "execute" => Ok(Operation::Execute),
+            _ => Err(format!("'{}' is not a valid value for Operation", s)),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            Operation::Read => "read",
+            Operation::Write => "write",
+            Operation::Execute => "execute",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+///A range of memory given by its base address and size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRange {
+    pub base: NativePointer,
     pub size: usize,
 }
 
-///Scan for occurences of `pattern` in memory range given by `address` and
-///`size`.
+impl From<(NativePointer, usize)> for MemoryRange {
+    fn from((base, size): (NativePointer, usize)) -> Self {
+        MemoryRange { base, size }
+    }
+}
+
+impl From<RangeDetails> for MemoryRange {
+    fn from(r: RangeDetails) -> Self {
+        MemoryRange {
+            base: r.base,
+            size: r.size,
+        }
+    }
+}
+
+impl From<&RangeDetails> for MemoryRange {
+    fn from(r: &RangeDetails) -> Self {
+        MemoryRange {
+            base: r.base.clone(),
+            size: r.size,
+        }
+    }
+}
+
+///Types with a known size and alignment in the memory of the instrumented
+///process.
+///
+///This is implemented automatically by `#[derive(FromMemory)]`.
+pub trait MemoryLayout {
+    ///Size of the type in bytes, including trailing padding.
+    fn size_of() -> usize;
+
+    ///Alignment of the type in bytes.
+    fn align_of() -> usize;
+}
+
+///Types that can be read from the memory of the instrumented process.
+///
+///This can be derived for structs with `#[derive(FromMemory)]`. Fields are
+///laid out following the rules of a C struct, using
+///[`get_pointer_size`](crate::process::get_pointer_size) for pointer-sized
+///fields. 8-byte integers and doubles are aligned to 4 bytes on i386 targets
+///other than Windows, as in the System V ABI.
+///
+///```
+///#[derive(FromMemory)]
+///struct SockaddrIn {
+///    sin_family: u16,
+///    sin_port: u16,
+///    sin_addr: u32,
+///    sin_zero: [u8; 8],
+///}
+///
+///let addr = SockaddrIn::from_memory(&args.get(1))?;
+///```
+pub trait FromMemory: MemoryLayout + Sized {
+    ///Read a value from `address`.
+    fn from_memory(address: &NativePointer) -> Result<Self, Error>;
+}
+
+///Types that can be written to the memory of the instrumented process.
+///
+///This can be derived for structs with `#[derive(ToMemory)]`.
+pub trait ToMemory: MemoryLayout {
+    ///Write the value to `address`.
+    fn to_memory(&self, address: &NativePointer) -> Result<(), Error>;
+}
+
+///Computes the field offsets of a C struct. Used by the code generated by
+///`#[derive(FromMemory)]` and `#[derive(ToMemory)]`.
+#[doc(hidden)]
+pub struct Layout {
+    offset: usize,
+    align: usize,
+}
+
+#[doc(hidden)]
+impl Layout {
+    pub fn new() -> Self {
+        Self {
+            offset: 0,
+            align: 1,
+        }
+    }
+
+    ///Place a field after the previous one and return its offset.
+    pub fn field(&mut self, size: usize, align: usize) -> usize {
+        let offset = align_up(self.offset, align);
+        self.field_at(offset, size, align)
+    }
+
+    ///Place a field at an explicit offset and return that offset.
+    pub fn field_at(&mut self, offset: usize, size: usize, align: usize) -> usize {
+        self.offset = offset + size;
+        self.align = self.align.max(align);
+        offset
+    }
+
+    pub fn size(&self) -> usize {
+        align_up(self.offset, self.align)
+    }
+
+    pub fn align(&self) -> usize {
+        self.align
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn align_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+///Alignment of 8-byte integers and doubles in a struct.
 ///
-///This is equivalent to calling `Memory.scan` in the JavaScript API.
-pub fn scan(address: &NativePointer, size: usize, pattern: &str) -> Vec<Match> {
-    frida_rs_sys::memory::scan(address.to_sys(), size, pattern.to_owned())
-        .iter()
-        .map(|x| {
-            let address = NativePointer::from_jsvalue(
-                js_sys::Reflect::get(&x, &wasm_bindgen::prelude::JsValue::from_str("address"))
-                    .unwrap(),
-            );
-            let size: usize =
-                js_sys::Reflect::get(&x, &wasm_bindgen::prelude::JsValue::from_str("size"))
-                    .unwrap()
-                    .as_f64()
-                    .unwrap() as usize;
-
-            Match { address, size }
-        })
-        .collect()
+///The i386 System V ABI only aligns them to 4 bytes, while Windows and the
+///other architectures supported by Frida align them to 8 bytes.
+fn align_of_8_byte(arch: &str, platform: &str) -> usize {
+    if arch == "ia32" && platform != "windows" {
+        4
+    } else {
+        8
+    }
+}
+
+macro_rules! impl_memory_primitive {
+    ($ty:ty, $size:expr, $read:ident, $write:ident) => {
+        impl_memory_primitive!($ty, $size, $size, $read, $write);
+    };
+    ($ty:ty, $size:expr, $align:expr, $read:ident, $write:ident) => {
+        impl MemoryLayout for $ty {
+            fn size_of() -> usize {
+                $size
+            }
+
+            fn align_of() -> usize {
+                $align
+            }
+        }
+
+        impl FromMemory for $ty {
+            fn from_memory(address: &NativePointer) -> Result<Self, Error> {
+                address.$read()
+            }
+        }
+
+        impl ToMemory for $ty {
+            fn to_memory(&self, address: &NativePointer) -> Result<(), Error> {
+                address.$write(*self)
+            }
+        }
+    };
+}
+
+impl_memory_primitive!(i8, 1, read_s8, write_s8);
+impl_memory_primitive!(u8, 1, read_u8, write_u8);
+impl_memory_primitive!(i16, 2, read_s16, write_s16);
+impl_memory_primitive!(u16, 2, read_u16, write_u16);
+impl_memory_primitive!(i32, 4, read_s32, write_s32);
+impl_memory_primitive!(u32, 4, read_u32, write_u32);
+impl_memory_primitive!(f32, 4, read_float, write_float);
+
+// The alignment of 8-byte values depends on the ABI of the instrumented
+// process, like the size of a pointer.
+impl_memory_primitive!(
+    i64,
+    8,
+    align_of_8_byte(crate::process::get_arch(), crate::process::get_platform()),
+    read_s64,
+    write_s64
+);
+impl_memory_primitive!(
+    u64,
+    8,
+    align_of_8_byte(crate::process::get_arch(), crate::process::get_platform()),
+    read_u64,
+    write_u64
+);
+impl_memory_primitive!(
+    f64,
+    8,
+    align_of_8_byte(crate::process::get_arch(), crate::process::get_platform()),
+    read_double,
+    write_double
+);
+
+impl MemoryLayout for NativePointer {
+    fn size_of() -> usize {
+        crate::process::get_pointer_size()
+    }
+
+    fn align_of() -> usize {
+        crate::process::get_pointer_size()
+    }
+}
+
+impl FromMemory for NativePointer {
+    fn from_memory(address: &NativePointer) -> Result<Self, Error> {
+        address.read_pointer()
+    }
+}
+
+impl ToMemory for NativePointer {
+    fn to_memory(&self, address: &NativePointer) -> Result<(), Error> {
+        address.write_pointer(self)
+    }
+}
+
+impl<T: MemoryLayout, const N: usize> MemoryLayout for [T; N] {
+    fn size_of() -> usize {
+        N * T::size_of()
+    }
+
+    fn align_of() -> usize {
+        T::align_of()
+    }
+}
+
+impl<T: FromMemory, const N: usize> FromMemory for [T; N] {
+    fn from_memory(address: &NativePointer) -> Result<Self, Error> {
+        let size = T::size_of();
+        let elements = (0..N)
+            .map(|i| T::from_memory(&(address + i * size)))
+            .collect::<Result<Vec<T>, Error>>()?;
+
+        match elements.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T: ToMemory, const N: usize> ToMemory for [T; N] {
+    fn to_memory(&self, address: &NativePointer) -> Result<(), Error> {
+        let size = T::size_of();
+        for (i, element) in self.iter().enumerate() {
+            element.to_memory(&(address + i * size))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn align_up_rounds_to_multiple() {
+        assert_eq!(align_up(0, 4), 0);
+        assert_eq!(align_up(1, 4), 4);
+        assert_eq!(align_up(4, 4), 4);
+        assert_eq!(align_up(5, 8), 8);
+        assert_eq!(align_up(7, 1), 7);
+    }
+
+    #[test]
+    fn layout_field_pads_to_alignment() {
+        let mut layout = Layout::new();
+        assert_eq!(layout.field(1, 1), 0);
+        assert_eq!(layout.field(4, 4), 4);
+        assert_eq!(layout.field(2, 2), 8);
+        assert_eq!(layout.align(), 4);
+        assert_eq!(layout.size(), 12);
+    }
+
+    #[test]
+    fn layout_field_at_moves_offset() {
+        let mut layout = Layout::new();
+        assert_eq!(layout.field(1, 1), 0);
+        assert_eq!(layout.field_at(0x10, 2, 2), 0x10);
+        assert_eq!(layout.field(4, 4), 0x14);
+        assert_eq!(layout.size(), 0x18);
+    }
+
+    #[test]
+    fn layout_empty() {
+        let layout = Layout::new();
+        assert_eq!(layout.size(), 0);
+        assert_eq!(layout.align(), 1);
+    }
+
+    #[test]
+    fn align_of_8_byte_depends_on_abi() {
+        assert_eq!(align_of_8_byte("ia32", "linux"), 4);
+        assert_eq!(align_of_8_byte("ia32", "darwin"), 4);
+        assert_eq!(align_of_8_byte("ia32", "windows"), 8);
+        assert_eq!(align_of_8_byte("x64", "linux"), 8);
+        assert_eq!(align_of_8_byte("arm", "linux"), 8);
+    }
+
+    #[derive(FromMemory, ToMemory)]
+    #[allow(dead_code)]
+    struct Header {
+        kind: u8,
+        length: u32,
+        flags: u16,
+    }
+
+    #[derive(FromMemory, ToMemory)]
+    #[allow(dead_code)]
+    struct Packet {
+        header: Header,
+        #[memory(array = 3)]
+        tag: [u8; 3],
+        #[memory(array = 2)]
+        ports: Vec<u16>,
+        #[memory(offset = 0x20)]
+        checksum: u32,
+    }
+
+    #[test]
+    fn derive_layout() {
+        assert_eq!(Header::size_of(), 12);
+        assert_eq!(Header::align_of(), 4);
+
+        // header: 0..12, tag: 12..15, ports: 16..20, checksum: 0x20..0x24
+        assert_eq!(Packet::size_of(), 0x24);
+        assert_eq!(Packet::align_of(), 4);
+    }
 }
@@ -0,0 +1,136 @@
+//!Frida functions for monitoring accesses to memory ranges.
+//!
+//!The functions in this module correspond to the JavaScript functions
+//!grouped under
+//![https://frida.re/docs/javascript-api/#memoryaccessmonitor](https://frida.re/docs/javascript-api/#memoryaccessmonitor).
+
+use crate::error::Error;
+use crate::fromsys::FromSys;
+use crate::memory::{MemoryRange, Operation};
+use crate::nativepointer::NativePointer;
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+///Details of a memory access reported to the callback of
+///[`enable`](crate::memory::access_monitor::enable).
+#[derive(Debug, Clone)]
+pub struct MemoryAccessDetails {
+    pub operation: Operation,
+    pub from: NativePointer,
+    pub address: NativePointer,
+    pub range_index: usize,
+    pub page_index: usize,
+    pub pages_completed: usize,
+    pub pages_total: usize,
+}
+
+impl From<frida_rs_sys::memoryaccessmonitor::MemoryAccessDetails> for MemoryAccessDetails {
+    fn from(m: frida_rs_sys::memoryaccessmonitor::MemoryAccessDetails) -> Self {
+        MemoryAccessDetails {
+            operation: m.operation().parse().unwrap(),
+            from: NativePointer::from_sys(m.from()),
+            address: NativePointer::from_sys(m.address()),
+            range_index: m.range_index(),
+            page_index: m.page_index(),
+            pages_completed: m.pages_completed(),
+            pages_total: m.pages_total(),
+        }
+    }
+}
+
+type OnAccess = Closure<dyn FnMut(frida_rs_sys::memoryaccessmonitor::MemoryAccessDetails)>;
+
+thread_local! {
+    // Identifies the monitor currently enabled, so that dropping a monitor
+    // that has since been replaced does not disable its replacement.
+    static ACTIVE: Cell<u64> = const { Cell::new(0) };
+}
+
+///Guard returned by [`enable`](crate::memory::access_monitor::enable).
+///
+///Monitoring stays enabled until the guard is dropped or
+///[`disable`](AccessMonitor::disable) is called.
+#[must_use = "monitoring is disabled as soon as the guard is dropped"]
+pub struct AccessMonitor {
+    id: u64,
+    _on_access: OnAccess,
+}
+
+impl AccessMonitor {
+    ///Stop monitoring the ranges.
+    ///
+    ///This is equivalent to calling `MemoryAccessMonitor.disable` in the
+    ///JavaScript API.
+    pub fn disable(self) {}
+}
+
+impl Drop for AccessMonitor {
+    fn drop(&mut self) {
+        if ACTIVE.with(|a| a.get()) == self.id {
+            frida_rs_sys::memoryaccessmonitor::disable();
+        }
+    }
+}
+
+///Monitor `ranges` and call `on_access` on the first access to each page.
+///
+///Each range is either a [`RangeDetails`](crate::range::RangeDetails) or a
+///`(NativePointer, usize)` pair of base address and size. Only one monitor
+///can be active at a time, enabling a new one replaces the previous one.
+///
+///This is equivalent to calling `MemoryAccessMonitor.enable` in the
+///JavaScript API.
+///
+///```
+///let monitor = access_monitor::enable(vec![(buffer.clone(), 0x1000)], |details| {
+///    console::log(&format!("{} of {} from {}", details.operation, details.address, details.from));
+///})?;
+///```
+pub fn enable<I, R, F>(ranges: I, mut on_access: F) -> Result<AccessMonitor, Error>
+where
+    I: IntoIterator<Item = R>,
+    R: Into<MemoryRange>,
+    F: FnMut(&MemoryAccessDetails) + 'static,
+{
+    let array = js_sys::Array::new();
+    for range in ranges {
+        let range = range.into();
+        let obj = js_sys::Object::new();
+        js_sys::Reflect::set(&obj, &JsValue::from_str("base"), range.base.to_sys()).unwrap();
+        js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("size"),
+            &JsValue::from_f64(range.size as f64),
+        )
+        .unwrap();
+        array.push(&obj);
+    }
+
+    let on_access: OnAccess = Closure::wrap(Box::new(
+        move |details: frida_rs_sys::memoryaccessmonitor::MemoryAccessDetails| {
+            on_access(&MemoryAccessDetails::from(details));
+        },
+    ));
+
+    let callbacks = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &callbacks,
+        &JsValue::from_str("onAccess"),
+        on_access.as_ref().unchecked_ref(),
+    )
+    .unwrap();
+
+    frida_rs_sys::memoryaccessmonitor::enable(&array, &callbacks).map_err(Error::from)?;
+
+    let id = ACTIVE.with(|a| {
+        let id = a.get() + 1;
+        a.set(id);
+        id
+    });
+
+    Ok(AccessMonitor {
+        id,
+        _on_access: on_access,
+    })
+}
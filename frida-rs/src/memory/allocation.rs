@@ -0,0 +1,115 @@
+use crate::error::Error;
+use crate::fromsys::FromSys;
+use crate::nativepointer::NativePointer;
+use std::ops::Deref;
+use wasm_bindgen::JsValue;
+
+///Memory allocated on the heap of the instrumented process.
+///
+///Frida frees the memory once the JavaScript object backing the allocation is
+///garbage collected. An `Allocation` keeps that object alive, so the memory
+///stays valid for as long as the `Allocation` is not dropped. Pointers derived
+///from it, for example with `&allocation + 4`, do not keep it alive.
+///
+///`Allocation` dereferences to the [`NativePointer`](crate::NativePointer)
+///of the start of the allocated memory.
+#[derive(Debug)]
+pub struct Allocation {
+    pointer: NativePointer,
+    size: usize,
+}
+
+impl Allocation {
+    pub(crate) fn new(pointer: frida_rs_sys::nativepointer::NativePointer, size: usize) -> Self {
+        Self {
+            pointer: NativePointer::from_sys(pointer),
+            size,
+        }
+    }
+
+    ///Size of the allocation in bytes.
+    ///
+    ///For allocations returned by
+    ///[`alloc_ansi_string`](crate::memory::alloc_ansi_string) this is an upper
+    ///bound as the exact size depends on the ANSI code page.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Deref for Allocation {
+    type Target = NativePointer;
+
+    fn deref(&self) -> &NativePointer {
+        &self.pointer
+    }
+}
+
+impl AsRef<NativePointer> for Allocation {
+    fn as_ref(&self) -> &NativePointer {
+        &self.pointer
+    }
+}
+
+///Allocate `size` bytes of memory on the heap of the instrumented process.
+///
+///This is equivalent to calling `Memory.alloc` in the JavaScript API.
+pub fn alloc(size: usize) -> Result<Allocation, Error> {
+    let pointer = frida_rs_sys::memory::alloc(size)?;
+    Ok(Allocation::new(pointer, size))
+}
+
+///Allocate `size` bytes of memory within `max_distance` bytes of `near`.
+///
+///This is useful for allocating trampolines that must be reachable from
+///`near` with a relative branch.
+///
+///This is equivalent to calling `Memory.alloc` with the `near` and
+///`maxDistance` options in the JavaScript API.
+pub fn alloc_near(
+    size: usize,
+    near: &NativePointer,
+    max_distance: usize,
+) -> Result<Allocation, Error> {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &JsValue::from_str("near"), near.to_sys()).unwrap();
+    js_sys::Reflect::set(
+        &options,
+        &JsValue::from_str("maxDistance"),
+        &JsValue::from_f64(max_distance as f64),
+    )
+    .unwrap();
+
+    let pointer = frida_rs_sys::memory::alloc_with_options(size, &options)?;
+    Ok(Allocation::new(pointer, size))
+}
+
+///Allocate `s` as a NUL-terminated UTF-8 string on the heap of the
+///instrumented process.
+///
+///This is equivalent to calling `Memory.allocUtf8String` in the JavaScript
+///API.
+pub fn alloc_utf8_string(s: &str) -> Result<Allocation, Error> {
+    let pointer = frida_rs_sys::memory::alloc_utf8_string(s)?;
+    Ok(Allocation::new(pointer, s.len() + 1))
+}
+
+///Allocate `s` as a NUL-terminated UTF-16 string on the heap of the
+///instrumented process.
+///
+///This is equivalent to calling `Memory.allocUtf16String` in the JavaScript
+///API.
+pub fn alloc_utf16_string(s: &str) -> Result<Allocation, Error> {
+    let pointer = frida_rs_sys::memory::alloc_utf16_string(s)?;
+    Ok(Allocation::new(pointer, (s.encode_utf16().count() + 1) * 2))
+}
+
+///Allocate `s` as a NUL-terminated string in the current ANSI code page on the
+///heap of the instrumented process. This is only available on Windows.
+///
+///This is equivalent to calling `Memory.allocAnsiString` in the JavaScript
+///API.
+pub fn alloc_ansi_string(s: &str) -> Result<Allocation, Error> {
+    let pointer = frida_rs_sys::memory::alloc_ansi_string(s)?;
+    Ok(Allocation::new(pointer, s.len() + 1))
+}
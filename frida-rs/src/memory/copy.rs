@@ -0,0 +1,46 @@
+use crate::error::Error;
+use crate::memory::{Allocation, Operation};
+use crate::nativepointer::NativePointer;
+
+///Copy `len` bytes from `src` to `dst`.
+///
+///The ranges may overlap, in which case the bytes are copied through a
+///temporary buffer.
+///
+///This is equivalent to calling `Memory.copy` in the JavaScript API.
+pub fn copy(dst: &NativePointer, src: &NativePointer, len: usize) -> Result<(), Error> {
+    let d = dst.to_u64();
+    let s = src.to_u64();
+    let len64 = len as u64;
+
+    if s < d.saturating_add(len64) && d < s.saturating_add(len64) {
+        let bytes = src.read_byte_array(len)?;
+        return dst.write_byte_array(&bytes);
+    }
+
+    frida_rs_sys::memory::copy(dst.to_sys(), src.to_sys(), len)
+        .map_err(|e| Error::from_memory_access(e, src, Operation::Read))
+}
+
+///Duplicate `len` bytes at `src` into a new allocation.
+///
+///This is equivalent to calling `Memory.dup` in the JavaScript API.
+pub fn dup(src: &NativePointer, len: usize) -> Result<Allocation, Error> {
+    let pointer = frida_rs_sys::memory::dup(src.to_sys(), len)
+        .map_err(|e| Error::from_memory_access(e, src, Operation::Read))?;
+    Ok(Allocation::new(pointer, len))
+}
+
+///Copy `dst.len()` bytes from `src` in the instrumented process into `dst`.
+///
+///The bytes are transferred in a single call into the JavaScript API.
+pub fn copy_to_slice(src: &NativePointer, dst: &mut [u8]) -> Result<(), Error> {
+    src.read_byte_array_into(dst)
+}
+
+///Copy the bytes of `src` to `dst` in the instrumented process.
+///
+///The bytes are transferred in a single call into the JavaScript API.
+pub fn copy_from_slice(dst: &NativePointer, src: &[u8]) -> Result<(), Error> {
+    dst.write_byte_array(src)
+}
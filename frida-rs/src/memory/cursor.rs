@@ -0,0 +1,209 @@
+use crate::nativepointer::NativePointer;
+use std::io;
+
+///Number of bytes fetched from the instrumented process at a time.
+const BUFFER_SIZE: usize = 4096;
+
+///Cursor over the memory of the instrumented process.
+///
+///This implements [`Read`](std::io::Read), [`BufRead`](std::io::BufRead),
+///[`Write`](std::io::Write) and [`Seek`](std::io::Seek) so that existing
+///parsers can consume memory of the instrumented process directly. Reads are
+///batched into an internal buffer to avoid crossing into JavaScript for every
+///byte.
+///
+///Positions are relative to the base address. If the cursor is created with
+///[`with_len`](Cursor::with_len), reads and writes stop at the end of the
+///bound.
+///
+///```
+///let mut cursor = Cursor::with_len(module.base.clone(), module.size);
+///let mut magic = [0; 4];
+///cursor.read_exact(&mut magic)?;
+///```
+#[derive(Debug)]
+pub struct Cursor {
+    base: NativePointer,
+    len: Option<usize>,
+    position: u64,
+    buffer: Vec<u8>,
+    buffer_position: u64,
+}
+
+impl Cursor {
+    ///Create an unbounded cursor starting at `base`.
+    pub fn new(base: NativePointer) -> Self {
+        Self {
+            base,
+            len: None,
+            position: 0,
+            buffer: Vec::new(),
+            buffer_position: 0,
+        }
+    }
+
+    ///Create a cursor over the `len` bytes starting at `base`.
+    pub fn with_len(base: NativePointer, len: usize) -> Self {
+        Self {
+            len: Some(len),
+            ..Self::new(base)
+        }
+    }
+
+    ///Get the base address of the cursor.
+    pub fn base(&self) -> &NativePointer {
+        &self.base
+    }
+
+    ///Get the number of bytes the cursor is bounded to, if any.
+    pub fn bound(&self) -> Option<usize> {
+        self.len
+    }
+
+    ///Get the current position of the cursor relative to the base address.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    ///Set the current position of the cursor relative to the base address.
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+
+    ///Get the address the cursor currently points to.
+    pub fn address(&self) -> NativePointer {
+        &self.base + self.position
+    }
+
+    ///Number of bytes that can be accessed at the current position, capped to
+    ///`max`.
+    fn remaining(&self, max: usize) -> usize {
+        match self.len {
+            Some(len) => (len as u64).saturating_sub(self.position).min(max as u64) as usize,
+            None => max,
+        }
+    }
+
+    fn buffered(&self) -> &[u8] {
+        let end = self.buffer_position + self.buffer.len() as u64;
+        if self.position >= self.buffer_position && self.position < end {
+            &self.buffer[(self.position - self.buffer_position) as usize..]
+        } else {
+            &[]
+        }
+    }
+
+    fn refill(&mut self) -> io::Result<()> {
+        let size = self.remaining(BUFFER_SIZE);
+        let address = self.address();
+
+        self.buffer.clear();
+        self.buffer.resize(size, 0);
+        self.buffer_position = self.position;
+
+        if address.read_byte_array_into(&mut self.buffer).is_ok() {
+            return Ok(());
+        }
+
+        // The buffer may span into an unmapped page. Retry up to the end of
+        // the current page so that the readable part is still returned.
+        let page_size = crate::process::get_page_size() as u64;
+        let page_offset = (address.to_u64() % page_size) as usize;
+        let size = size.min(page_size as usize - page_offset);
+
+        self.buffer.truncate(size);
+        if let Err(e) = address.read_byte_array_into(&mut self.buffer) {
+            self.buffer.clear();
+            return Err(io::Error::other(e.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+impl io::Read for Cursor {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let available = io::BufRead::fill_buf(self)?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        io::BufRead::consume(self, n);
+        Ok(n)
+    }
+}
+
+impl io::BufRead for Cursor {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.buffered().is_empty() {
+            self.refill()?;
+        }
+        Ok(self.buffered())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt as u64;
+    }
+}
+
+impl io::Write for Cursor {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.remaining(buf.len());
+        if n == 0 {
+            return Ok(0);
+        }
+
+        self.address()
+            .write_byte_array(&buf[..n])
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        // The buffered bytes may be stale now.
+        self.buffer.clear();
+        self.position += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for Cursor {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let (origin, offset) = match pos {
+            io::SeekFrom::Start(n) => {
+                self.position = n;
+                return Ok(n);
+            }
+            io::SeekFrom::Current(n) => (self.position, n),
+            io::SeekFrom::End(n) => match self.len {
+                Some(len) => (len as u64, n),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "cannot seek from the end of an unbounded cursor",
+                    ))
+                }
+            },
+        };
+
+        let position = if offset >= 0 {
+            origin.checked_add(offset as u64)
+        } else {
+            origin.checked_sub(offset.unsigned_abs())
+        };
+
+        match position {
+            Some(n) => {
+                self.position = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
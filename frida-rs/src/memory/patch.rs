@@ -0,0 +1,49 @@
+use crate::error::Error;
+use crate::fromsys::FromSys;
+use crate::memory::Operation;
+use crate::nativepointer::NativePointer;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+///Safely modify `size` bytes of code at `address`.
+///
+///`apply` is called with a writable pointer that the new code must be written
+///to. This is not necessarily the same location as `address`, as some
+///platforms require modifications to be written to a temporary location that
+///is then mapped on top of the original page. Frida takes care of making the
+///code writable and restoring its protection afterwards.
+///
+///Returns [`Error::CodeSigningRequired`](crate::Error::CodeSigningRequired)
+///without calling `apply` if the
+///[code signing policy](crate::process::get_code_signing_policy) of the
+///instrumented process is `"required"`, as modifying code would crash the
+///process. Errors returned by `apply` are propagated.
+///
+///This is equivalent to calling `Memory.patchCode` in the JavaScript API.
+///
+///```
+///memory::patch_code(&target, 4, |code: NativePointer| {
+///    code.write_byte_array(&[0x1f, 0x20, 0x03, 0xd5])
+///})?;
+///```
+pub fn patch_code<F>(address: &NativePointer, size: usize, apply: F) -> Result<(), Error>
+where
+    F: FnOnce(NativePointer) -> Result<(), Error> + 'static,
+{
+    if crate::process::get_code_signing_policy() == "required" {
+        return Err(Error::CodeSigningRequired);
+    }
+
+    let result = Rc::new(RefCell::new(Ok(())));
+    let r = result.clone();
+    let closure = Closure::once(move |code: frida_rs_sys::nativepointer::NativePointer| {
+        *r.borrow_mut() = apply(NativePointer::from_sys(code));
+    });
+
+    frida_rs_sys::memory::patch_code(address.to_sys(), size, closure.as_ref().unchecked_ref())
+        .map_err(|e| Error::from_memory_access(e, address, Operation::Write))?;
+
+    result.replace(Ok(()))
+}
@@ -0,0 +1,198 @@
+use crate::error::Error;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str;
+
+///Byte pattern used to scan memory.
+///
+///A pattern is a sequence of bytes where each bit can be ignored with a mask.
+///It prints as the match pattern strings used by the JavaScript API, such as
+///`"48 8b ?? ?? e8"` where `?` is a wildcard nibble, or `"13 37 : 1f ff"`
+///where the bytes after `:` are a mask.
+///
+///Use the [`pattern!`](crate::memory::pattern) macro to validate a pattern
+///string at compile time.
+///
+///```
+///let p = Pattern::parse("48 8b ?? ?? e8")?;
+///let p = Pattern::from_bytes(b"\x7fELF");
+///let p = Pattern::with_mask(&[0x13, 0x37], &[0x1f, 0xff])?;
+///```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    bytes: Vec<u8>,
+    mask: Vec<u8>,
+}
+
+impl Pattern {
+    ///Create a pattern matching `bytes` exactly.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+            mask: vec![0xff; bytes.len()],
+        }
+    }
+
+    ///Create a pattern matching `bytes` where only the bits set in `mask` are
+    ///compared.
+    ///
+    ///Returns [`Error::InvalidPattern`](crate::Error::InvalidPattern) if
+    ///`bytes` and `mask` differ in length.
+    pub fn with_mask(bytes: &[u8], mask: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != mask.len() {
+            return Err(Error::InvalidPattern(
+                "mask must have the same length as the pattern".to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            bytes: bytes.iter().zip(mask).map(|(b, m)| b & m).collect(),
+            mask: mask.to_vec(),
+        })
+    }
+
+    ///Parse a match pattern string as accepted by the JavaScript API.
+    ///
+    ///Returns [`Error::InvalidPattern`](crate::Error::InvalidPattern) if the
+    ///string is malformed.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let (bytes, mask) = frida_rs_pattern::parse(s).map_err(Error::InvalidPattern)?;
+        Ok(Self { bytes, mask })
+    }
+
+    ///Number of bytes matched by the pattern.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    ///Check if the pattern is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl str::FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Pattern {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&String> for Pattern {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl From<&Pattern> for Pattern {
+    fn from(pattern: &Pattern) -> Self {
+        pattern.clone()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nibble_mask = |m: u8| m & 0xf0 == 0 || m & 0xf0 == 0xf0;
+        let wildcards = self
+            .mask
+            .iter()
+            .all(|m| nibble_mask(*m) && nibble_mask(*m << 4));
+
+        let mut tokens = Vec::with_capacity(self.bytes.len());
+        for (b, m) in self.bytes.iter().zip(&self.mask) {
+            if !wildcards {
+                tokens.push(format!("{:02x}", b));
+                continue;
+            }
+
+            let hi = if m & 0xf0 != 0 {
+                format!("{:x}", b >> 4)
+            } else {
+                "?".to_owned()
+            };
+            let lo = if m & 0x0f != 0 {
+                format!("{:x}", b & 0x0f)
+            } else {
+                "?".to_owned()
+            };
+            tokens.push(hi + &lo);
+        }
+        write!(f, "{}", tokens.join(" "))?;
+
+        if !wildcards {
+            let mask = self
+                .mask
+                .iter()
+                .map(|m| format!("{:02x}", m))
+                .collect::<Vec<_>>();
+            write!(f, " : {}", mask.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wildcards() {
+        let p = Pattern::parse("48 8b ?? 4? e8").unwrap();
+        assert_eq!(p.bytes, [0x48, 0x8b, 0x00, 0x40, 0xe8]);
+        assert_eq!(p.mask, [0xff, 0xff, 0x00, 0xf0, 0xff]);
+    }
+
+    #[test]
+    fn parse_mask() {
+        let p = Pattern::parse("13 37 : 1f ff").unwrap();
+        assert_eq!(p, Pattern::with_mask(&[0x13, 0x37], &[0x1f, 0xff]).unwrap());
+        assert_eq!(p.bytes, [0x13, 0x37]);
+    }
+
+    #[test]
+    fn parse_ignores_whitespace() {
+        assert_eq!(
+            Pattern::parse("deadbeef").unwrap(),
+            Pattern::parse(" de ad\tbe ef ").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for s in &["", "4", "4g", "de ad : ff", "de : ?f", "de ad : ff ff ff"] {
+            match Pattern::parse(s) {
+                Err(Error::InvalidPattern(_)) => {}
+                r => panic!("'{}' parsed as {:?}", s, r),
+            }
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in &["48 8b ?? 4? e8", "13 37 : 1f ff", "7f 45 4c 46"] {
+            let p = Pattern::parse(s).unwrap();
+            assert_eq!(p.to_string(), *s);
+            assert_eq!(Pattern::parse(&p.to_string()).unwrap(), p);
+        }
+    }
+
+    #[test]
+    fn try_from_str() {
+        assert_eq!(
+            Pattern::try_from("7f 45 4c 46").unwrap(),
+            Pattern::from_bytes(b"\x7fELF")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::error::Error;
+use crate::nativepointer::NativePointer;
+use bitflags::bitflags;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str;
+
+bitflags! {
+    ///Memory protection of a range in the instrumented process.
+    ///
+    ///This parses from and prints as the `"rwx"` strings used by the
+    ///JavaScript API, where `"r-x"` means readable and executable. When used
+    ///as a filter, for example in
+    ///[`enumerate_ranges`](crate::process::enumerate_ranges), it means "at
+    ///least" the given protection.
+    pub struct Protection: u8 {
+        const READ = 0b001;
+        const WRITE = 0b010;
+        const EXECUTE = 0b100;
+    }
+}
+
+impl str::FromStr for Protection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.as_bytes();
+        if chars.len() != 3 {
+            return Err(format!("'{}' is not a valid value for Protection", s));
+        }
+
+        let mut protection = Protection::empty();
+        for (c, (flag, letter)) in chars.iter().zip(&[
+            (Protection::READ, b'r'),
+            (Protection::WRITE, b'w'),
+            (Protection::EXECUTE, b'x'),
+        ]) {
+            if c == letter {
+                protection |= *flag;
+            } else if *c != b'-' {
+                return Err(format!("'{}' is not a valid value for Protection", s));
+            }
+        }
+
+        Ok(protection)
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let r = if self.contains(Protection::READ) {
+            'r'
+        } else {
+            '-'
+        };
+        let w = if self.contains(Protection::WRITE) {
+            'w'
+        } else {
+            '-'
+        };
+        let x = if self.contains(Protection::EXECUTE) {
+            'x'
+        } else {
+            '-'
+        };
+        write!(f, "{}{}{}", r, w, x)
+    }
+}
+
+impl Serialize for Protection {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Protection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(ProtectionVisitor)
+    }
+}
+
+struct ProtectionVisitor;
+
+impl<'de> Visitor<'de> for ProtectionVisitor {
+    type Value = Protection;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a protection string such as \"rw-\"")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+///Change the protection of the memory range given by `address` and `size`.
+///
+///Returns `true` if the protection was changed successfully.
+///
+///This is equivalent to calling `Memory.protect` in the JavaScript API.
+pub fn protect(address: &NativePointer, size: usize, protection: Protection) -> bool {
+    frida_rs_sys::memory::protect(address.to_sys(), size, &protection.to_string())
+}
+
+///Get the protection of the page containing `address`.
+///
+///This is equivalent to calling `Memory.queryProtection` in the JavaScript
+///API.
+pub fn query_protection(address: &NativePointer) -> Result<Protection, Error> {
+    let protection = frida_rs_sys::memory::query_protection(address.to_sys())?;
+    Ok(protection.parse().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for (s, p) in &[
+            ("rwx", Protection::all()),
+            ("r-x", Protection::READ | Protection::EXECUTE),
+            ("rw-", Protection::READ | Protection::WRITE),
+            ("---", Protection::empty()),
+        ] {
+            assert_eq!(s.parse::<Protection>().unwrap(), *p);
+            assert_eq!(p.to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        for s in &["", "rw", "rwxx", "rwz", "wrx", "RWX"] {
+            assert!(s.parse::<Protection>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn serde() {
+        let p = Protection::READ | Protection::EXECUTE;
+        assert_eq!(serde_json::to_string(&p).unwrap(), "\"r-x\"");
+        assert_eq!(serde_json::from_str::<Protection>("\"r-x\"").unwrap(), p);
+        assert!(serde_json::from_str::<Protection>("\"rwz\"").is_err());
+        assert!(serde_json::from_str::<Protection>("5").is_err());
+    }
+}
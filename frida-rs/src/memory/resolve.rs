@@ -0,0 +1,361 @@
+use crate::error::Error;
+use crate::module;
+use crate::nativepointer::NativePointer;
+use crate::process;
+use std::fmt;
+
+///Reason a [`resolve`](crate::memory::resolve) step failed.
+#[derive(Debug, Clone)]
+pub enum ResolveErrorKind {
+    ///The expression is malformed.
+    Syntax(String),
+    ///No module or export is named after the symbol.
+    UnknownSymbol(String),
+    ///The pointer read from `address` is null.
+    NullPointer { address: NativePointer },
+    ///`address` could not be read.
+    Unreadable { address: NativePointer },
+}
+
+///Error returned by [`resolve`](crate::memory::resolve).
+#[derive(Debug, Clone)]
+pub struct ResolveError {
+    ///Index of the failing step. Step 0 is the base expression and step `n`
+    ///follows the `n`th `->`.
+    pub step: usize,
+    ///Text of the failing step.
+    pub expression: String,
+    pub kind: ResolveErrorKind,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {} (`{}`): ", self.step, self.expression)?;
+        match &self.kind {
+            ResolveErrorKind::Syntax(message) => write!(f, "{}", message),
+            ResolveErrorKind::UnknownSymbol(name) => write!(f, "unknown symbol '{}'", name),
+            ResolveErrorKind::NullPointer { address } => {
+                write!(f, "null pointer read from {}", address)
+            }
+            ResolveErrorKind::Unreadable { address } => write!(f, "cannot read {}", address),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+impl From<ResolveError> for Error {
+    fn from(e: ResolveError) -> Self {
+        Error::Resolve(e)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Term {
+    Number(u64),
+    Symbol(String),
+}
+
+///Parse the `+`/`-` separated terms of a single step.
+fn parse_terms(text: &str) -> Result<Vec<(bool, Term)>, String> {
+    let mut terms = Vec::new();
+    let mut chars = text.trim().chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let negative = match chars.peek() {
+            None => break,
+            Some('+') => {
+                chars.next();
+                false
+            }
+            Some('-') => {
+                chars.next();
+                true
+            }
+            Some(_) if terms.is_empty() => false,
+            Some(c) => return Err(format!("expected '+' or '-', found '{}'", c)),
+        };
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let term = match chars.peek() {
+            None => return Err("expected a term".to_string()),
+            Some('"') => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => name.push(c),
+                        None => return Err("unterminated quoted symbol".to_string()),
+                    }
+                }
+                Term::Symbol(name)
+            }
+            Some(_) => {
+                let mut atom = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '+' || c == '-' {
+                        break;
+                    }
+                    atom.push(c);
+                    chars.next();
+                }
+
+                if atom.starts_with(|c: char| c.is_ascii_digit()) {
+                    let digits = atom
+                        .strip_prefix("0x")
+                        .or_else(|| atom.strip_prefix("0X"))
+                        .unwrap_or(&atom);
+                    let n = u64::from_str_radix(digits, 16)
+                        .map_err(|_| format!("'{}' is not a valid hexadecimal number", atom))?;
+                    Term::Number(n)
+                } else {
+                    Term::Symbol(atom)
+                }
+            }
+        };
+
+        terms.push((negative, term));
+    }
+
+    Ok(terms)
+}
+
+///Resolve `symbol` to a module base address or export address.
+fn resolve_symbol(symbol: &str) -> Option<NativePointer> {
+    if let Some((module_name, export_name)) = symbol.split_once('!') {
+        return process::get_module_by_name(module_name)?
+            .enumerate_exports()
+            .into_iter()
+            .find(|e| e.name == export_name)
+            .map(|e| e.address);
+    }
+
+    module::get_base_address(symbol).or_else(|| module::get_export(symbol))
+}
+
+///Reason a step failed, with addresses as integers.
+#[derive(Debug, PartialEq)]
+enum Failure {
+    Syntax(String),
+    UnknownSymbol(String),
+    NullPointer(u64),
+    Unreadable(u64),
+}
+
+///Evaluate the `->` separated `steps` of an expression.
+///
+///`read_pointer` reads the pointer at an address and `resolve_symbol`
+///resolves a symbol, so the evaluation does not depend on the instrumented
+///process. Failures are returned along with the index of the failing step.
+fn evaluate<R, S>(
+    steps: &[&str],
+    mut read_pointer: R,
+    mut resolve_symbol: S,
+) -> Result<u64, (usize, Failure)>
+where
+    R: FnMut(u64) -> Option<u64>,
+    S: FnMut(&str) -> Option<u64>,
+{
+    // Check the whole expression before touching any memory.
+    let mut parsed = Vec::with_capacity(steps.len());
+    for (i, text) in steps.iter().enumerate() {
+        let terms = parse_terms(text).map_err(|m| (i, Failure::Syntax(m)))?;
+        if i == 0 && terms.is_empty() {
+            return Err((0, Failure::Syntax("expected a base address".to_string())));
+        }
+        parsed.push(terms);
+    }
+
+    let mut value = 0u64;
+    for (i, terms) in parsed.into_iter().enumerate() {
+        if i > 0 {
+            let address = value;
+            value = match read_pointer(address) {
+                Some(0) => return Err((i, Failure::NullPointer(address))),
+                Some(p) => p,
+                None => return Err((i, Failure::Unreadable(address))),
+            };
+        }
+
+        for (negative, term) in terms {
+            let operand = match term {
+                Term::Number(n) => n,
+                Term::Symbol(name) => match resolve_symbol(&name) {
+                    Some(p) => p,
+                    None => return Err((i, Failure::UnknownSymbol(name))),
+                },
+            };
+
+            value = if negative {
+                value.wrapping_sub(operand)
+            } else {
+                value.wrapping_add(operand)
+            };
+        }
+    }
+
+    Ok(value)
+}
+
+///Resolve a pointer-chain expression to an address.
+///
+///An expression is made of steps separated by `->`. Each step is a sum of
+///terms joined by `+` or `-`. A term is either:
+///
+///* A hexadecimal number, with or without the `0x` prefix. Terms starting
+///  with a digit are always numbers.
+///* A module name, standing for the base address of the module.
+///* An export name, or `module!export` for an export of a given module.
+///
+///Symbols containing `+`, `-` or whitespace can be quoted, such as
+///`"libstdc++.so.6"`. Every `->` reads the pointer at the current address
+///before the terms of the next step are added to it. No pointer is read
+///after the last step.
+///
+///```
+///let health = memory::resolve("libgame.so+0x1a2b0 -> +0x18 -> +0x40")?;
+///```
+pub fn resolve(expression: &str) -> Result<NativePointer, ResolveError> {
+    let steps = expression.split("->").map(str::trim).collect::<Vec<_>>();
+
+    let read_pointer = |address: u64| {
+        NativePointer::from(address)
+            .read_pointer()
+            .ok()
+            .map(|p| p.to_u64())
+    };
+    let symbol = |name: &str| resolve_symbol(name).map(|p| p.to_u64());
+
+    match evaluate(&steps, read_pointer, symbol) {
+        Ok(value) => Ok(NativePointer::from(value)),
+        Err((step, failure)) => Err(ResolveError {
+            step,
+            expression: steps[step].to_string(),
+            kind: match failure {
+                Failure::Syntax(message) => ResolveErrorKind::Syntax(message),
+                Failure::UnknownSymbol(name) => ResolveErrorKind::UnknownSymbol(name),
+                Failure::NullPointer(address) => ResolveErrorKind::NullPointer {
+                    address: NativePointer::from(address),
+                },
+                Failure::Unreadable(address) => ResolveErrorKind::Unreadable {
+                    address: NativePointer::from(address),
+                },
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn symbol(name: &str) -> Option<u64> {
+        match name {
+            "libgame.so" => Some(0x1000),
+            "libstdc++.so.6" => Some(0x2000),
+            "libc.so!open" => Some(0x3000),
+            _ => None,
+        }
+    }
+
+    fn eval(expression: &str, memory: &[(u64, u64)]) -> Result<u64, (usize, Failure)> {
+        let memory = memory.iter().copied().collect::<HashMap<_, _>>();
+        let steps = expression.split("->").map(str::trim).collect::<Vec<_>>();
+        evaluate(&steps, |a| memory.get(&a).copied(), symbol)
+    }
+
+    #[test]
+    fn parse_numbers_and_symbols() {
+        let terms = parse_terms("libgame.so + 0x10 - 8").unwrap();
+        assert_eq!(
+            terms,
+            vec![
+                (false, Term::Symbol("libgame.so".to_string())),
+                (false, Term::Number(0x10)),
+                (true, Term::Number(8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_leading_sign_and_quotes() {
+        let terms = parse_terms("+\"libstdc++.so.6\"-0X1f").unwrap();
+        assert_eq!(
+            terms,
+            vec![
+                (false, Term::Symbol("libstdc++.so.6".to_string())),
+                (true, Term::Number(0x1f)),
+            ]
+        );
+        assert_eq!(parse_terms("  ").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for text in &["a b", "a +", "\"libc", "0xzz", "1g"] {
+            assert!(parse_terms(text).is_err(), "'{}' parsed", text);
+        }
+    }
+
+    #[test]
+    fn evaluate_chain() {
+        let memory = [(0x1010, 0x5000), (0x5018, 0x6000)];
+        assert_eq!(eval("libgame.so+0x10", &memory), Ok(0x1010));
+        assert_eq!(
+            eval("libgame.so+0x10 -> +0x18 -> +0x40", &memory),
+            Ok(0x6040)
+        );
+        assert_eq!(eval("\"libc.so!open\" - 1", &memory), Ok(0x2fff));
+    }
+
+    #[test]
+    fn evaluate_syntax_errors() {
+        assert!(matches!(eval("", &[]), Err((0, Failure::Syntax(_)))));
+        assert!(matches!(
+            eval("libgame.so -> +zz q", &[]),
+            Err((1, Failure::Syntax(_)))
+        ));
+    }
+
+    #[test]
+    fn evaluate_unknown_symbol() {
+        assert_eq!(
+            eval("libgame.so -> libfoo.so", &[(0x1000, 0x5000)]),
+            Err((1, Failure::UnknownSymbol("libfoo.so".to_string())))
+        );
+    }
+
+    #[test]
+    fn evaluate_null_pointer() {
+        let memory = [(0x1010, 0x5000), (0x5018, 0)];
+        assert_eq!(
+            eval("libgame.so+0x10 -> +0x18 -> +0x40", &memory),
+            Err((2, Failure::NullPointer(0x5018)))
+        );
+    }
+
+    #[test]
+    fn evaluate_unreadable() {
+        let memory = [(0x1010, 0x5000)];
+        assert_eq!(
+            eval("libgame.so+0x10 -> +0x18 -> +0x40", &memory),
+            Err((2, Failure::Unreadable(0x5018)))
+        );
+    }
+
+    #[test]
+    fn evaluate_checks_syntax_before_reading() {
+        let steps = ["libgame.so", "+0x8", "+"];
+        let result = evaluate(&steps, |_| panic!("memory was read"), symbol);
+        assert!(matches!(result, Err((2, Failure::Syntax(_)))));
+    }
+}
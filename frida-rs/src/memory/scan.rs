@@ -0,0 +1,231 @@
+use crate::error::Error;
+use crate::fromsys::FromSys;
+use crate::memory::{Operation, Pattern};
+use crate::nativepointer::NativePointer;
+use futures_core::Stream;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+///Matched values returned by [`scan`](crate::memory::scan) and
+///[`scan_async`](crate::memory::scan_async).
+pub struct Match {
+    pub address: NativePointer,
+    pub size: usize,
+}
+
+///Scan for occurences of `pattern` in memory range given by `address` and
+///`size`.
+///
+///`pattern` is either a [`Pattern`](crate::memory::Pattern) or a match
+///pattern string, which is parsed before scanning.
+///
+///This is equivalent to calling `Memory.scanSync` in the JavaScript API.
+///
+///```
+///let matches = memory::scan(&module.base, module.size, "de ad ?? ef")?;
+///```
+pub fn scan<P>(address: &NativePointer, size: usize, pattern: P) -> Result<Vec<Match>, Error>
+where
+    P: TryInto<Pattern>,
+    P::Error: Into<Error>,
+{
+    let pattern: Pattern = pattern.try_into().map_err(Into::into)?;
+    let matches = frida_rs_sys::memory::scan(address.to_sys(), size, pattern.to_string())
+        .map_err(|e| Error::from_memory_access(e, address, Operation::Read))?;
+
+    Ok(matches
+        .iter()
+        .map(|x| {
+            let address = NativePointer::from_jsvalue(
+                js_sys::Reflect::get(&x, &JsValue::from_str("address")).unwrap(),
+            );
+            let size: usize = js_sys::Reflect::get(&x, &JsValue::from_str("size"))
+                .unwrap()
+                .as_f64()
+                .unwrap() as usize;
+
+            Match { address, size }
+        })
+        .collect())
+}
+
+///Returned by the `on_match` callback of
+///[`scan_async`](crate::memory::scan_async) to decide whether the scan goes
+///on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanAction {
+    Continue,
+    Stop,
+}
+
+#[derive(Default)]
+struct ScanState {
+    matches: VecDeque<Result<Match, Error>>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl ScanState {
+    fn push(&mut self, m: Result<Match, Error>) {
+        self.matches.push_back(m);
+        self.wake();
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+type OnMatch = Closure<dyn FnMut(frida_rs_sys::nativepointer::NativePointer, usize) -> JsValue>;
+type OnError = Closure<dyn FnMut(JsValue)>;
+type OnComplete = Closure<dyn FnMut()>;
+
+///Stream of matches produced by [`scan_async`](crate::memory::scan_async).
+///
+///The stream ends once the scan completes, fails or is stopped. An error
+///reported by Frida is yielded as the last item. Dropping the stream stops
+///the scan at the next match.
+pub struct Scan {
+    state: Rc<RefCell<ScanState>>,
+}
+
+impl Stream for Scan {
+    type Item = Result<Match, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.borrow_mut();
+
+        if let Some(m) = state.matches.pop_front() {
+            return Poll::Ready(Some(m));
+        }
+
+        if state.done {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Scan {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.done = true;
+        state.matches.clear();
+    }
+}
+
+///Scan for occurences of `pattern` in memory range given by `address` and
+///`size` without blocking the JavaScript thread.
+///
+///`on_match` is called for every match before it is yielded by the returned
+///[`Scan`](crate::memory::Scan) stream. Returning
+///[`ScanAction::Stop`](crate::memory::ScanAction::Stop) aborts the scan after
+///that match. Like [`scan`](crate::memory::scan), `pattern` is either a
+///[`Pattern`](crate::memory::Pattern) or a match pattern string.
+///
+///This is equivalent to calling `Memory.scan` in the JavaScript API.
+///
+///```
+///let mut matches = memory::scan_async(&module.base, module.size, &pattern!("de ad be ef"), |_| {
+///    ScanAction::Continue
+///})?;
+///
+///while let Some(m) = matches.next().await {
+///    ...
+///}
+///```
+pub fn scan_async<P, F>(
+    address: &NativePointer,
+    size: usize,
+    pattern: P,
+    mut on_match: F,
+) -> Result<Scan, Error>
+where
+    P: TryInto<Pattern>,
+    P::Error: Into<Error>,
+    F: FnMut(&Match) -> ScanAction + 'static,
+{
+    let pattern: Pattern = pattern.try_into().map_err(Into::into)?;
+    let state = Rc::new(RefCell::new(ScanState::default()));
+
+    // The closures must outlive the call to `Memory.scan`. They are kept
+    // alive by this cell and released once Frida reports that the scan has
+    // ended.
+    let closures: Rc<RefCell<Option<(OnMatch, OnError, OnComplete)>>> = Rc::new(RefCell::new(None));
+
+    let s = state.clone();
+    let on_match: OnMatch = Closure::wrap(Box::new(
+        move |address: frida_rs_sys::nativepointer::NativePointer, size: usize| {
+            let mut state = s.borrow_mut();
+            if state.done {
+                return JsValue::from_str("stop");
+            }
+
+            let m = Match {
+                address: NativePointer::from_sys(address),
+                size,
+            };
+            let action = on_match(&m);
+            state.push(Ok(m));
+
+            match action {
+                ScanAction::Continue => JsValue::UNDEFINED,
+                ScanAction::Stop => {
+                    state.finish();
+                    JsValue::from_str("stop")
+                }
+            }
+        },
+    ));
+
+    let s = state.clone();
+    let c = closures.clone();
+    let on_error: OnError = Closure::wrap(Box::new(move |reason: JsValue| {
+        let mut state = s.borrow_mut();
+        if !state.done {
+            state.push(Err(Error::from(reason)));
+        }
+        state.finish();
+        c.borrow_mut().take();
+    }));
+
+    let s = state.clone();
+    let c = closures.clone();
+    let on_complete: OnComplete = Closure::wrap(Box::new(move || {
+        s.borrow_mut().finish();
+        c.borrow_mut().take();
+    }));
+
+    let callbacks = js_sys::Object::new();
+    for (name, f) in [
+        ("onMatch", on_match.as_ref()),
+        ("onError", on_error.as_ref()),
+        ("onComplete", on_complete.as_ref()),
+    ]
+    .iter()
+    {
+        js_sys::Reflect::set(&callbacks, &JsValue::from_str(name), f.unchecked_ref()).unwrap();
+    }
+
+    frida_rs_sys::memory::scan_async(address.to_sys(), size, pattern.to_string(), &callbacks)
+        .map_err(|e| Error::from_memory_access(e, address, Operation::Read))?;
+
+    closures.replace(Some((on_match, on_error, on_complete)));
+
+    Ok(Scan { state })
+}
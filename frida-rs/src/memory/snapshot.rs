@@ -0,0 +1,339 @@
+use crate::memory::{MemoryRange, Protection};
+use crate::module::Module;
+use crate::modulemap::ModuleMap;
+use crate::nativepointer::NativePointer;
+use crate::process;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+
+///Contiguous bytes read from a range.
+struct Chunk {
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+struct Region {
+    range: MemoryRange,
+    module: Option<String>,
+    chunks: Vec<Chunk>,
+}
+
+///Read the contents of `range`.
+///
+///If the range cannot be read at once, it is read page by page and the
+///pages that fault are left out.
+fn read_chunks(range: &MemoryRange) -> Vec<Chunk> {
+    if let Ok(bytes) = range.base.read_byte_array(range.size) {
+        return vec![Chunk { offset: 0, bytes }];
+    }
+
+    let page_size = process::get_page_size();
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut offset = 0;
+
+    while offset < range.size {
+        let len = (page_size - (range.base.to_u64() as usize + offset) % page_size)
+            .min(range.size - offset);
+
+        if let Ok(bytes) = (&range.base + offset).read_byte_array(len) {
+            match chunks.last_mut() {
+                Some(c) if c.offset + c.bytes.len() == offset => c.bytes.extend(bytes),
+                _ => chunks.push(Chunk { offset, bytes }),
+            }
+        }
+
+        offset += len;
+    }
+
+    chunks
+}
+
+///Copy of the contents of a set of memory ranges at a point in time.
+///
+///The contents are kept in wasm memory. Use [`diff`](Snapshot::diff) to find
+///the bytes that have changed since the snapshot was captured.
+///
+///```
+///let snapshot = Snapshot::capture(process::enumerate_ranges(Protection::READ | Protection::WRITE));
+///...
+///send(&snapshot.diff());
+///```
+pub struct Snapshot {
+    regions: Vec<Region>,
+}
+
+impl Snapshot {
+    ///Capture the contents of `ranges`.
+    ///
+    ///Each range is either a [`RangeDetails`](crate::range::RangeDetails) or
+    ///a `(NativePointer, usize)` pair of base address and size. Pages that
+    ///cannot be read, such as guard pages, are left out of the snapshot.
+    pub fn capture<I, R>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<MemoryRange>,
+    {
+        let modules = ModuleMap::new();
+        let regions = ranges
+            .into_iter()
+            .map(|r| {
+                let range = r.into();
+                Region {
+                    module: modules.get_name(&range.base),
+                    chunks: read_chunks(&range),
+                    range,
+                }
+            })
+            .collect();
+
+        Snapshot { regions }
+    }
+
+    ///Capture the contents of the readable ranges of `module`.
+    pub fn capture_module(module: &Module) -> Self {
+        let regions = module
+            .enumerate_ranges(Protection::READ)
+            .into_iter()
+            .map(|r| {
+                let range = MemoryRange::from(r);
+                Region {
+                    module: Some(module.name.clone()),
+                    chunks: read_chunks(&range),
+                    range,
+                }
+            })
+            .collect();
+
+        Snapshot { regions }
+    }
+
+    ///Number of bytes held by the snapshot.
+    pub fn size(&self) -> usize {
+        self.regions
+            .iter()
+            .flat_map(|r| r.chunks.iter())
+            .map(|c| c.bytes.len())
+            .sum()
+    }
+
+    ///Compare the snapshot with the current contents of its ranges.
+    ///
+    ///Only ranges with changes are part of the returned
+    ///[`Diff`](crate::memory::Diff). Bytes that can no longer be read are
+    ///not reported as changed.
+    pub fn diff(&self) -> Diff {
+        let mut ranges = Vec::new();
+
+        for region in self.regions.iter() {
+            let base = &region.range.base;
+            let changes = diff_chunks(&region.chunks, |offset, size| {
+                read_chunks(&MemoryRange {
+                    base: base + offset,
+                    size,
+                })
+            })
+            .into_iter()
+            .map(|r| Change {
+                address: base + r.offset,
+                old: r.old,
+                new: r.new,
+            })
+            .collect::<Vec<_>>();
+
+            if !changes.is_empty() {
+                ranges.push(RangeDiff {
+                    range: region.range.clone(),
+                    module: region.module.clone(),
+                    changes,
+                });
+            }
+        }
+
+        Diff { ranges }
+    }
+}
+
+///Run of changed bytes at an offset from the base of a region.
+#[derive(Debug, PartialEq)]
+struct Run {
+    offset: usize,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+///Compare the chunks of a region with its current contents.
+///
+///`read` reads `size` bytes at an offset from the base of the region and
+///returns the chunks that could be read, with offsets relative to that
+///offset. Bytes that can no longer be read are not compared.
+fn diff_chunks<F>(chunks: &[Chunk], mut read: F) -> Vec<Run>
+where
+    F: FnMut(usize, usize) -> Vec<Chunk>,
+{
+    let mut runs = Vec::new();
+
+    for chunk in chunks.iter() {
+        for c in read(chunk.offset, chunk.bytes.len()) {
+            let old = &chunk.bytes[c.offset..c.offset + c.bytes.len()];
+            diff_bytes(chunk.offset + c.offset, old, &c.bytes, &mut runs);
+        }
+    }
+
+    runs
+}
+
+///Append the runs of bytes that differ between `old` and `new` to `runs`.
+///`offset` is the offset of the first byte from the base of the region.
+fn diff_bytes(offset: usize, old: &[u8], new: &[u8], runs: &mut Vec<Run>) {
+    let mut i = 0;
+
+    while i < old.len() {
+        if old[i] == new[i] {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < old.len() && old[i] != new[i] {
+            i += 1;
+        }
+
+        runs.push(Run {
+            offset: offset + start,
+            old: old[start..i].to_vec(),
+            new: new[start..i].to_vec(),
+        });
+    }
+}
+
+///Run of changed bytes.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    ///Address of the first changed byte.
+    pub address: NativePointer,
+    ///Contents at the time of the snapshot.
+    pub old: Vec<u8>,
+    ///Current contents.
+    pub new: Vec<u8>,
+}
+
+///Changes within a single range of a [`Snapshot`](crate::memory::Snapshot).
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeDiff {
+    pub range: MemoryRange,
+    ///Name of the module containing the range, if any.
+    pub module: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+///Changes found by [`Snapshot::diff`](crate::memory::Snapshot::diff).
+#[derive(Debug, Clone, Serialize)]
+pub struct Diff {
+    pub ranges: Vec<RangeDiff>,
+}
+
+impl Diff {
+    ///Check if no bytes have changed.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    ///Iterate over all changes regardless of their range.
+    pub fn changes(&self) -> impl Iterator<Item = &Change> {
+        self.ranges.iter().flat_map(|r| r.changes.iter())
+    }
+
+    ///Group the changed ranges by the name of the module containing them.
+    ///
+    ///Ranges outside of any module are grouped under `None`.
+    pub fn by_module(&self) -> BTreeMap<Option<&str>, Vec<&RangeDiff>> {
+        let mut modules: BTreeMap<Option<&str>, Vec<&RangeDiff>> = BTreeMap::new();
+        for r in self.ranges.iter() {
+            modules.entry(r.module.as_deref()).or_default().push(r);
+        }
+        modules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(offset: usize, old: &[u8], new: &[u8]) -> Run {
+        Run {
+            offset,
+            old: old.to_vec(),
+            new: new.to_vec(),
+        }
+    }
+
+    fn chunk(offset: usize, bytes: &[u8]) -> Chunk {
+        Chunk {
+            offset,
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    fn diff(old: &[u8], new: &[u8]) -> Vec<Run> {
+        let mut runs = Vec::new();
+        diff_bytes(0, old, new, &mut runs);
+        runs
+    }
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(diff(&[1, 2, 3], &[1, 2, 3]), vec![]);
+    }
+
+    #[test]
+    fn adjacent_bytes_form_one_run() {
+        assert_eq!(
+            diff(&[0, 1, 2, 3, 0], &[0, 9, 9, 9, 0]),
+            vec![run(1, &[1, 2, 3], &[9, 9, 9])]
+        );
+    }
+
+    #[test]
+    fn runs_split_by_unchanged_byte() {
+        assert_eq!(
+            diff(&[0, 1, 0, 2, 0], &[0, 9, 0, 8, 0]),
+            vec![run(1, &[1], &[9]), run(3, &[2], &[8])]
+        );
+    }
+
+    #[test]
+    fn runs_at_range_edges() {
+        assert_eq!(
+            diff(&[1, 0, 0, 2], &[9, 0, 0, 8]),
+            vec![run(0, &[1], &[9]), run(3, &[2], &[8])]
+        );
+        assert_eq!(diff(&[1, 2], &[9, 8]), vec![run(0, &[1, 2], &[9, 8])]);
+    }
+
+    #[test]
+    fn offsets_are_relative_to_region() {
+        let chunks = [chunk(0x10, &[0, 1, 2, 3])];
+        let runs = diff_chunks(&chunks, |offset, size| {
+            assert_eq!((offset, size), (0x10, 4));
+            vec![chunk(0, &[0, 1, 9, 3])]
+        });
+        assert_eq!(runs, vec![run(0x12, &[2], &[9])]);
+    }
+
+    #[test]
+    fn vanished_range_has_no_changes() {
+        let chunks = [chunk(0, &[1, 2, 3, 4])];
+        assert_eq!(diff_chunks(&chunks, |_, _| Vec::new()), vec![]);
+    }
+
+    #[test]
+    fn partially_vanished_range() {
+        // Only the second half of the chunk can still be read.
+        let chunks = [chunk(0, &[1, 2, 3, 4]), chunk(0x100, &[5, 6])];
+        let runs = diff_chunks(&chunks, |offset, _| match offset {
+            0 => vec![chunk(2, &[9, 9])],
+            _ => Vec::new(),
+        });
+        assert_eq!(runs, vec![run(2, &[3, 4], &[9, 9])]);
+    }
+}
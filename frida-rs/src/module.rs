@@ -4,9 +4,11 @@
 //!grouped under
 //![https://frida.re/docs/javascript-api/#module](https://frida.re/docs/javascript-api/#module).
 use crate::fromsys::FromSys;
+use crate::memory::Protection;
 use crate::nativepointer::NativePointer;
 use crate::range::RangeDetails;
 use frida_rs_sys::module;
+use serde_derive::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
 
 ///Get the base address of the module named `name`.
@@ -50,6 +52,7 @@ pub fn get_export(export_name: &str) -> Option<NativePointer> {
 ////     Some(p)
 //// }
 
+#[derive(Serialize)]
 pub struct Module {
     ///Canonical module name.
     pub name: String,
@@ -63,6 +66,7 @@ pub struct Module {
     ///Full filesystem path of module.
     pub path: String,
 
+    #[serde(skip)]
     sys: frida_rs_sys::module::Module,
 }
 
@@ -147,14 +151,15 @@ impl Module {
 
     ///Get all memory ranges satisfying `protection`.
     ///
-    ///`protection` is a string with the form "rwx" where "rw-" means "must be
-    ///at least readable and writable."
+    ///`protection` is the minimum protection of the returned ranges, i.e.
+    ///`Protection::READ | Protection::WRITE` means "must be at least readable
+    ///and writable."
     ///
     ///This is the equivalent to calling `enumerateRanges()` in the
     ///JavaScript API.
-    pub fn enumerate_ranges(&self, protection: &str) -> Vec<RangeDetails> {
+    pub fn enumerate_ranges(&self, protection: Protection) -> Vec<RangeDetails> {
         self.sys
-            .enumerate_ranges(protection)
+            .enumerate_ranges(&protection.to_string())
             .iter()
             .map(frida_rs_sys::range::RangeDetails::from)
             .map(RangeDetails::from)
@@ -169,6 +174,7 @@ impl Module {
     //    // }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct ExportDetails {
     pub export_type: String, // TODO: This should be a Enum type
     pub name: String,
@@ -185,6 +191,7 @@ impl From<module::ExportDetails> for ExportDetails {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct ImportDetails {
     pub import_type: Option<String>, // TODO: This should be a Enum type
     pub name: String,
@@ -205,6 +212,7 @@ impl From<module::ImportDetails> for ImportDetails {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct SymbolDetails {
     pub is_global: bool,
     pub symbol_type: String, // TODO: This should be a Enum type
@@ -227,16 +235,17 @@ impl From<module::SymbolDetails> for SymbolDetails {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct SymbolSectionDetails {
     pub id: String,
-    pub protection: String,
+    pub protection: Protection,
 }
 
 impl From<module::SymbolSectionDetails> for SymbolSectionDetails {
     fn from(m: module::SymbolSectionDetails) -> Self {
         SymbolSectionDetails {
             id: m.id(),
-            protection: m.protection(),
+            protection: m.protection().parse().unwrap(),
         }
     }
 }
@@ -0,0 +1,283 @@
+//!Typed native functions and callbacks.
+//!
+//!The types in this module correspond to the JavaScript classes documented
+//!under
+//![https://frida.re/docs/javascript-api/#nativefunction](https://frida.re/docs/javascript-api/#nativefunction)
+//!and
+//![https://frida.re/docs/javascript-api/#nativecallback](https://frida.re/docs/javascript-api/#nativecallback).
+
+use crate::error::Error;
+use crate::fromsys::FromSys;
+use crate::nativepointer::NativePointer;
+use frida_rs_sys::int64::{Int64, UInt64};
+use frida_rs_sys::utils::args_wrap;
+use std::fmt;
+use std::marker::PhantomData;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+///Types that can be passed to and returned from native functions.
+pub trait NativeType: Sized {
+    ///Name of the type in the JavaScript API, such as `"int32"`.
+    const TYPE: &'static str;
+
+    fn to_js(&self) -> JsValue;
+
+    fn from_js(value: JsValue) -> Self;
+}
+
+impl NativeType for () {
+    const TYPE: &'static str = "void";
+
+    fn to_js(&self) -> JsValue {
+        JsValue::UNDEFINED
+    }
+
+    fn from_js(_value: JsValue) -> Self {}
+}
+
+impl NativeType for bool {
+    const TYPE: &'static str = "bool";
+
+    fn to_js(&self) -> JsValue {
+        JsValue::from_f64(if *self { 1.0 } else { 0.0 })
+    }
+
+    fn from_js(value: JsValue) -> Self {
+        value
+            .as_bool()
+            .unwrap_or_else(|| value.as_f64().unwrap_or_default() != 0.0)
+    }
+}
+
+macro_rules! impl_native_type_number {
+    ($($t:ty => $name:expr),* $(,)?) => {
+        $(
+            impl NativeType for $t {
+                const TYPE: &'static str = $name;
+
+                fn to_js(&self) -> JsValue {
+                    JsValue::from_f64(*self as f64)
+                }
+
+                fn from_js(value: JsValue) -> Self {
+                    value.as_f64().unwrap_or_default() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_native_type_number! {
+    i8 => "int8",
+    u8 => "uint8",
+    i16 => "int16",
+    u16 => "uint16",
+    i32 => "int32",
+    u32 => "uint32",
+    f32 => "float",
+    f64 => "double",
+}
+
+macro_rules! impl_native_type_64 {
+    ($($t:ty => $name:expr, $sys:ty),* $(,)?) => {
+        $(
+            impl NativeType for $t {
+                const TYPE: &'static str = $name;
+
+                fn to_js(&self) -> JsValue {
+                    <$sys>::new(&self.to_string()).into()
+                }
+
+                fn from_js(value: JsValue) -> Self {
+                    match value.as_f64() {
+                        Some(n) => n as $t,
+                        None => value.unchecked_into::<$sys>().to_string().parse().unwrap(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_native_type_64! {
+    i64 => "int64", Int64,
+    u64 => "uint64", UInt64,
+}
+
+impl NativeType for NativePointer {
+    const TYPE: &'static str = "pointer";
+
+    fn to_js(&self) -> JsValue {
+        self.to_sys().clone().into()
+    }
+
+    fn from_js(value: JsValue) -> Self {
+        NativePointer::from_jsvalue(value)
+    }
+}
+
+///Argument lists of native functions.
+///
+///This is implemented for tuples of up to 10 [`NativeType`]s.
+pub trait NativeArgs: Sized {
+    fn types() -> js_sys::Array;
+
+    fn to_js(&self) -> js_sys::Array;
+
+    fn from_js(args: js_sys::Array) -> Self;
+}
+
+macro_rules! impl_native_args {
+    ($($t:ident $i:tt),*) => {
+        impl<$($t: NativeType),*> NativeArgs for ($($t,)*) {
+            fn types() -> js_sys::Array {
+                let types = js_sys::Array::new();
+                $( types.push(&JsValue::from_str($t::TYPE)); )*
+                types
+            }
+
+            #[allow(unused_variables)]
+            fn to_js(&self) -> js_sys::Array {
+                let args = js_sys::Array::new();
+                $( args.push(&self.$i.to_js()); )*
+                args
+            }
+
+            #[allow(unused_variables, clippy::unused_unit)]
+            fn from_js(args: js_sys::Array) -> Self {
+                ($( $t::from_js(args.get($i)), )*)
+            }
+        }
+    };
+}
+
+impl_native_args!();
+impl_native_args!(A 0);
+impl_native_args!(A 0, B 1);
+impl_native_args!(A 0, B 1, C 2);
+impl_native_args!(A 0, B 1, C 2, D 3);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_native_args!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+
+///Native function taking the arguments `A` and returning `R`.
+///
+///This is equivalent to the `NativeFunction` class in the JavaScript API.
+///
+///```
+///let open = NativeFunction::<(NativePointer, i32), i32>::new(&module::get_export("open").unwrap());
+///let fd = open.call((path, 0))?;
+///```
+pub struct NativeFunction<A, R> {
+    sys: frida_rs_sys::nativefunction::NativeFunction,
+    _marker: PhantomData<fn(A) -> R>,
+}
+
+// Implemented by hand as deriving would require `A` and `R` to implement the
+// traits too.
+impl<A, R> Clone for NativeFunction<A, R> {
+    fn clone(&self) -> Self {
+        Self {
+            sys: self.sys.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, R> fmt::Debug for NativeFunction<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("NativeFunction").field(&self.sys).finish()
+    }
+}
+
+impl<A: NativeArgs, R: NativeType> NativeFunction<A, R> {
+    ///Create a function calling the native code at `address`.
+    pub fn new(address: &NativePointer) -> Self {
+        Self {
+            sys: frida_rs_sys::nativefunction::NativeFunction::new(
+                address.to_sys(),
+                R::TYPE,
+                &A::types(),
+            ),
+            _marker: PhantomData,
+        }
+    }
+
+    ///Call the function with `args`.
+    pub fn call(&self, args: A) -> Result<R, Error> {
+        let f: &js_sys::Function = self.sys.as_ref();
+        let ret = f.apply(&JsValue::NULL, &args.to_js())?;
+        Ok(R::from_js(ret))
+    }
+
+    ///Address of the function.
+    pub fn address(&self) -> NativePointer {
+        let p: &frida_rs_sys::nativepointer::NativePointer = self.sys.as_ref();
+        NativePointer::from_sys(p.clone())
+    }
+}
+
+type Trampoline = Closure<dyn FnMut(js_sys::Array) -> JsValue>;
+
+///Type-erased part of a [`NativeCallback`] that must stay alive for as long
+///as native code may call it.
+pub(crate) struct RawCallback {
+    pub(crate) sys: frida_rs_sys::nativefunction::NativeCallback,
+    _trampoline: Trampoline,
+}
+
+///Native function implemented by a Rust closure taking the arguments `A` and
+///returning `R`.
+///
+///The closure is freed when the callback is dropped, so native code must not
+///call the callback afterwards.
+///
+///This is equivalent to the `NativeCallback` class in the JavaScript API.
+///
+///```
+///let callback = NativeCallback::new(|(a, b): (i32, i32)| -> i32 { a + b });
+///```
+pub struct NativeCallback<A, R> {
+    pub(crate) raw: RawCallback,
+    _marker: PhantomData<fn(A) -> R>,
+}
+
+impl<A: NativeArgs + 'static, R: NativeType + 'static> NativeCallback<A, R> {
+    ///Create a callback calling `f`.
+    pub fn new<F>(mut f: F) -> Self
+    where
+        F: FnMut(A) -> R + 'static,
+    {
+        let trampoline: Trampoline = Closure::wrap(Box::new(move |args: js_sys::Array| {
+            f(A::from_js(args)).to_js()
+        }));
+
+        // NativeCallback passes the arguments individually, gather them into
+        // an array for the trampoline.
+        let adapter = args_wrap(trampoline.as_ref().unchecked_ref());
+
+        Self {
+            raw: RawCallback {
+                sys: frida_rs_sys::nativefunction::NativeCallback::new(
+                    &adapter,
+                    R::TYPE,
+                    &A::types(),
+                ),
+                _trampoline: trampoline,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, R> NativeCallback<A, R> {
+    ///Address of the callback.
+    pub fn address(&self) -> NativePointer {
+        let p: &frida_rs_sys::nativepointer::NativePointer = self.raw.sys.as_ref();
+        NativePointer::from_sys(p.clone())
+    }
+}
@@ -1,7 +1,24 @@
+use crate::error::Error;
 use crate::fromsys::FromSys;
+use crate::memory::Operation;
+use frida_rs_sys::int64::{Int64, UInt64};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops;
+use wasm_bindgen::JsValue;
 
-#[derive(Debug)]
+///Largest integer that can be represented exactly by a JavaScript number.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+///Pointer on the instrumented target.
+///
+///This is equivalent to the `NativePointer` class in the JavaScript API. The
+///memory accessors never throw: reading or writing unmapped memory returns an
+///[`Error::AccessViolation`](crate::Error::AccessViolation) instead.
+#[derive(Debug, Clone)]
 pub struct NativePointer(frida_rs_sys::nativepointer::NativePointer);
 
 impl NativePointer {
@@ -13,8 +30,465 @@ impl NativePointer {
         Self(frida_rs_sys::nativepointer::NativePointer::from_i32(s))
     }
 
-    pub fn read_u8(&self) -> u8 {
-        self.0.read_u8()
+    ///Check if the pointer is NULL.
+    ///
+    ///This is equivalent to calling `NativePointer.isNull` in the JavaScript
+    ///API.
+    pub fn is_null(&self) -> bool {
+        self.0.is_null_pointer()
+    }
+
+    ///Get the address of the pointer as an integer.
+    ///
+    ///This conversion is lossless for 64-bit targets.
+    pub fn to_u64(&self) -> u64 {
+        let s = self.0.to_string_radix(16);
+        u64::from_str_radix(&s, 16).unwrap()
+    }
+
+    ///Get the low 32 bits of the address as a signed integer.
+    ///
+    ///This is equivalent to calling `NativePointer.toInt32` in the JavaScript
+    ///API.
+    pub fn to_i32(&self) -> i32 {
+        self.0.to_int32()
+    }
+
+    ///Get the low 32 bits of the address as an unsigned integer.
+    ///
+    ///This is equivalent to calling `NativePointer.toUInt32` in the
+    ///JavaScript API.
+    pub fn to_u32(&self) -> u32 {
+        self.0.to_uint32()
+    }
+
+    ///Read a signed 8-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readS8` in the JavaScript
+    ///API.
+    pub fn read_s8(&self) -> Result<i8, Error> {
+        self.0.read_s8().map_err(|e| self.read_error(e))
+    }
+
+    ///Read an unsigned 8-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readU8` in the JavaScript
+    ///API.
+    pub fn read_u8(&self) -> Result<u8, Error> {
+        self.0.read_u8().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a signed 16-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readS16` in the JavaScript
+    ///API.
+    pub fn read_s16(&self) -> Result<i16, Error> {
+        self.0.read_s16().map_err(|e| self.read_error(e))
+    }
+
+    ///Read an unsigned 16-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readU16` in the JavaScript
+    ///API.
+    pub fn read_u16(&self) -> Result<u16, Error> {
+        self.0.read_u16().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a signed 32-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readS32` in the JavaScript
+    ///API.
+    pub fn read_s32(&self) -> Result<i32, Error> {
+        self.0.read_s32().map_err(|e| self.read_error(e))
+    }
+
+    ///Read an unsigned 32-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readU32` in the JavaScript
+    ///API.
+    pub fn read_u32(&self) -> Result<u32, Error> {
+        self.0.read_u32().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a signed 64-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readS64` in the JavaScript
+    ///API.
+    pub fn read_s64(&self) -> Result<i64, Error> {
+        self.0
+            .read_s64()
+            .map(|x| i64_from_sys(&x))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read an unsigned 64-bit integer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readU64` in the JavaScript
+    ///API.
+    pub fn read_u64(&self) -> Result<u64, Error> {
+        self.0
+            .read_u64()
+            .map(|x| u64_from_sys(&x))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `short` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readShort` in the
+    ///JavaScript API.
+    pub fn read_short(&self) -> Result<i16, Error> {
+        self.0.read_short().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `unsigned short` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readUShort` in the
+    ///JavaScript API.
+    pub fn read_ushort(&self) -> Result<u16, Error> {
+        self.0.read_ushort().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `int` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readInt` in the JavaScript
+    ///API.
+    pub fn read_int(&self) -> Result<i32, Error> {
+        self.0.read_int().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `unsigned int` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readUInt` in the
+    ///JavaScript API.
+    pub fn read_uint(&self) -> Result<u32, Error> {
+        self.0.read_uint().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `long` from this location.
+    ///
+    ///The size of a `long` depends on the platform of the instrumented
+    ///process, but the value is always widened to 64 bits.
+    ///
+    ///This is equivalent to calling `NativePointer.readLong` in the
+    ///JavaScript API.
+    pub fn read_long(&self) -> Result<i64, Error> {
+        self.0
+            .read_long()
+            .map(|x| i64_from_sys(&x))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a C `unsigned long` from this location.
+    ///
+    ///The size of an `unsigned long` depends on the platform of the
+    ///instrumented process, but the value is always widened to 64 bits.
+    ///
+    ///This is equivalent to calling `NativePointer.readULong` in the
+    ///JavaScript API.
+    pub fn read_ulong(&self) -> Result<u64, Error> {
+        self.0
+            .read_ulong()
+            .map(|x| u64_from_sys(&x))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a `float` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readFloat` in the
+    ///JavaScript API.
+    pub fn read_float(&self) -> Result<f32, Error> {
+        self.0.read_float().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a `double` from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readDouble` in the
+    ///JavaScript API.
+    pub fn read_double(&self) -> Result<f64, Error> {
+        self.0.read_double().map_err(|e| self.read_error(e))
+    }
+
+    ///Read a pointer from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readPointer` in the
+    ///JavaScript API.
+    pub fn read_pointer(&self) -> Result<NativePointer, Error> {
+        self.0
+            .read_pointer()
+            .map(Self)
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read `length` bytes from this location.
+    ///
+    ///This is equivalent to calling `NativePointer.readByteArray` in the
+    ///JavaScript API.
+    pub fn read_byte_array(&self, length: usize) -> Result<Vec<u8>, Error> {
+        self.0
+            .read_byte_array(length)
+            .map(|x| js_sys::Uint8Array::new(&x).to_vec())
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read `buf.len()` bytes from this location into `buf`.
+    ///
+    ///This is equivalent to calling `NativePointer.readByteArray` in the
+    ///JavaScript API.
+    pub fn read_byte_array_into(&self, buf: &mut [u8]) -> Result<(), Error> {
+        self.0
+            .read_byte_array(buf.len())
+            .map(|x| js_sys::Uint8Array::new(&x).copy_to(buf))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a NUL-terminated C string from this location.
+    ///
+    ///If `size` is provided, at most `size` bytes are read. Returns `None` if
+    ///this pointer is NULL.
+    ///
+    ///This is equivalent to calling `NativePointer.readCString` in the
+    ///JavaScript API.
+    pub fn read_c_string(&self, size: Option<usize>) -> Result<Option<String>, Error> {
+        self.0
+            .read_c_string(string_size(size))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a NUL-terminated UTF-8 string from this location.
+    ///
+    ///If `size` is provided, at most `size` bytes are read. Returns `None` if
+    ///this pointer is NULL.
+    ///
+    ///This is equivalent to calling `NativePointer.readUtf8String` in the
+    ///JavaScript API.
+    pub fn read_utf8_string(&self, size: Option<usize>) -> Result<Option<String>, Error> {
+        self.0
+            .read_utf8_string(string_size(size))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a NUL-terminated UTF-16 string from this location.
+    ///
+    ///If `length` is provided, at most `length` characters are read. Returns
+    ///`None` if this pointer is NULL.
+    ///
+    ///This is equivalent to calling `NativePointer.readUtf16String` in the
+    ///JavaScript API.
+    pub fn read_utf16_string(&self, length: Option<usize>) -> Result<Option<String>, Error> {
+        self.0
+            .read_utf16_string(string_size(length))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Read a NUL-terminated string in the current ANSI code page from this
+    ///location. This is only available on Windows.
+    ///
+    ///If `size` is provided, at most `size` bytes are read. Returns `None` if
+    ///this pointer is NULL.
+    ///
+    ///This is equivalent to calling `NativePointer.readAnsiString` in the
+    ///JavaScript API.
+    pub fn read_ansi_string(&self, size: Option<usize>) -> Result<Option<String>, Error> {
+        self.0
+            .read_ansi_string(string_size(size))
+            .map_err(|e| self.read_error(e))
+    }
+
+    ///Write a signed 8-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeS8` in the JavaScript
+    ///API.
+    pub fn write_s8(&self, value: i8) -> Result<(), Error> {
+        self.0.write_s8(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write an unsigned 8-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeU8` in the JavaScript
+    ///API.
+    pub fn write_u8(&self, value: u8) -> Result<(), Error> {
+        self.0.write_u8(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a signed 16-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeS16` in the JavaScript
+    ///API.
+    pub fn write_s16(&self, value: i16) -> Result<(), Error> {
+        self.0.write_s16(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write an unsigned 16-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeU16` in the JavaScript
+    ///API.
+    pub fn write_u16(&self, value: u16) -> Result<(), Error> {
+        self.0.write_u16(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a signed 32-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeS32` in the JavaScript
+    ///API.
+    pub fn write_s32(&self, value: i32) -> Result<(), Error> {
+        self.0.write_s32(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write an unsigned 32-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeU32` in the JavaScript
+    ///API.
+    pub fn write_u32(&self, value: u32) -> Result<(), Error> {
+        self.0.write_u32(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a signed 64-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeS64` in the JavaScript
+    ///API.
+    pub fn write_s64(&self, value: i64) -> Result<(), Error> {
+        self.0
+            .write_s64(&i64_to_sys(value))
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write an unsigned 64-bit integer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeU64` in the JavaScript
+    ///API.
+    pub fn write_u64(&self, value: u64) -> Result<(), Error> {
+        self.0
+            .write_u64(&u64_to_sys(value))
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `short` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeShort` in the
+    ///JavaScript API.
+    pub fn write_short(&self, value: i16) -> Result<(), Error> {
+        self.0.write_short(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `unsigned short` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeUShort` in the
+    ///JavaScript API.
+    pub fn write_ushort(&self, value: u16) -> Result<(), Error> {
+        self.0.write_ushort(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `int` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeInt` in the
+    ///JavaScript API.
+    pub fn write_int(&self, value: i32) -> Result<(), Error> {
+        self.0.write_int(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `unsigned int` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeUInt` in the
+    ///JavaScript API.
+    pub fn write_uint(&self, value: u32) -> Result<(), Error> {
+        self.0.write_uint(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `long` to this location.
+    ///
+    ///The value is truncated if a `long` is narrower than 64 bits on the
+    ///platform of the instrumented process.
+    ///
+    ///This is equivalent to calling `NativePointer.writeLong` in the
+    ///JavaScript API.
+    pub fn write_long(&self, value: i64) -> Result<(), Error> {
+        self.0
+            .write_long(&i64_to_sys(value))
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write a C `unsigned long` to this location.
+    ///
+    ///The value is truncated if an `unsigned long` is narrower than 64 bits
+    ///on the platform of the instrumented process.
+    ///
+    ///This is equivalent to calling `NativePointer.writeULong` in the
+    ///JavaScript API.
+    pub fn write_ulong(&self, value: u64) -> Result<(), Error> {
+        self.0
+            .write_ulong(&u64_to_sys(value))
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write a `float` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeFloat` in the
+    ///JavaScript API.
+    pub fn write_float(&self, value: f32) -> Result<(), Error> {
+        self.0.write_float(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a `double` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeDouble` in the
+    ///JavaScript API.
+    pub fn write_double(&self, value: f64) -> Result<(), Error> {
+        self.0.write_double(value).map_err(|e| self.write_error(e))
+    }
+
+    ///Write a pointer to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writePointer` in the
+    ///JavaScript API.
+    pub fn write_pointer(&self, value: &NativePointer) -> Result<(), Error> {
+        self.0
+            .write_pointer(&value.0)
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write `bytes` to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeByteArray` in the
+    ///JavaScript API.
+    pub fn write_byte_array(&self, bytes: &[u8]) -> Result<(), Error> {
+        self.0
+            .write_byte_array(&js_sys::Uint8Array::from(bytes).buffer())
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write `s` as a NUL-terminated UTF-8 string to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeUtf8String` in the
+    ///JavaScript API.
+    pub fn write_utf8_string(&self, s: &str) -> Result<(), Error> {
+        self.0.write_utf8_string(s).map_err(|e| self.write_error(e))
+    }
+
+    ///Write `s` as a NUL-terminated UTF-16 string to this location.
+    ///
+    ///This is equivalent to calling `NativePointer.writeUtf16String` in the
+    ///JavaScript API.
+    pub fn write_utf16_string(&self, s: &str) -> Result<(), Error> {
+        self.0
+            .write_utf16_string(s)
+            .map_err(|e| self.write_error(e))
+    }
+
+    ///Write `s` as a NUL-terminated string in the current ANSI code page to
+    ///this location. This is only available on Windows.
+    ///
+    ///This is equivalent to calling `NativePointer.writeAnsiString` in the
+    ///JavaScript API.
+    pub fn write_ansi_string(&self, s: &str) -> Result<(), Error> {
+        self.0.write_ansi_string(s).map_err(|e| self.write_error(e))
+    }
+
+    fn read_error(&self, e: JsValue) -> Error {
+        Error::from_memory_access(e, self, Operation::Read)
+    }
+
+    fn write_error(&self, e: JsValue) -> Error {
+        Error::from_memory_access(e, self, Operation::Write)
     }
 
     pub(crate) fn to_sys(&self) -> &frida_rs_sys::nativepointer::NativePointer {
@@ -36,8 +510,241 @@ impl FromSys<frida_rs_sys::nativepointer::NativePointer> for NativePointer {
     }
 }
 
+impl From<u64> for NativePointer {
+    fn from(s: u64) -> Self {
+        Self::new(&format!("{:#x}", s))
+    }
+}
+
+impl From<NativePointer> for u64 {
+    fn from(s: NativePointer) -> Self {
+        s.to_u64()
+    }
+}
+
 impl fmt::Display for NativePointer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0.to_string())
     }
 }
+
+impl Serialize for NativePointer {
+    ///Serialize the pointer as a `"0x…"` string, the same encoding used by
+    ///the JavaScript API.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for NativePointer {
+    ///Deserialize the pointer from a `"0x…"` string, a decimal string or an
+    ///integer.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NativePointerVisitor)
+    }
+}
+
+struct NativePointerVisitor;
+
+impl<'de> Visitor<'de> for NativePointerVisitor {
+    type Value = NativePointer;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a pointer as a \"0x\"-prefixed string or an integer")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(NativePointer::from(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(NativePointer::from(v as u64))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let parsed = match v.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => v.parse(),
+        };
+
+        parsed
+            .map(NativePointer::from)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+impl fmt::LowerHex for NativePointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.to_u64(), f)
+    }
+}
+
+impl PartialEq for NativePointer {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.equals(other.0.as_ref())
+    }
+}
+
+impl Eq for NativePointer {}
+
+impl PartialOrd for NativePointer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NativePointer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.compare(other.0.as_ref()).cmp(&0)
+    }
+}
+
+impl Hash for NativePointer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_u64().hash(state);
+    }
+}
+
+fn i64_from_sys(v: &Int64) -> i64 {
+    v.to_string().parse().unwrap()
+}
+
+fn i64_to_sys(v: i64) -> Int64 {
+    Int64::new(&v.to_string())
+}
+
+fn u64_from_sys(v: &UInt64) -> u64 {
+    v.to_string().parse().unwrap()
+}
+
+fn u64_to_sys(v: u64) -> UInt64 {
+    UInt64::new(&v.to_string())
+}
+
+///Convert an optional string size to the `-1` sentinel used by Frida to mean
+///"read until the NUL terminator".
+fn string_size(size: Option<usize>) -> i32 {
+    size.map(|s| s as i32).unwrap_or(-1)
+}
+
+///Right-hand side of an arithmetic or bitwise operation on a
+///[`NativePointer`](NativePointer).
+trait Operand {
+    fn to_operand(&self) -> JsValue;
+}
+
+impl Operand for u64 {
+    fn to_operand(&self) -> JsValue {
+        // Values that do not fit in a JavaScript number are passed as a
+        // NativePointer so no precision is lost.
+        if *self <= MAX_SAFE_INTEGER {
+            JsValue::from_f64(*self as f64)
+        } else {
+            NativePointer::from(*self).0.into()
+        }
+    }
+}
+
+impl Operand for usize {
+    fn to_operand(&self) -> JsValue {
+        (*self as u64).to_operand()
+    }
+}
+
+impl Operand for NativePointer {
+    fn to_operand(&self) -> JsValue {
+        self.0.clone().into()
+    }
+}
+
+impl Operand for &NativePointer {
+    fn to_operand(&self) -> JsValue {
+        self.0.clone().into()
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $sys:ident) => {
+        impl_binary_op!(@rhs $trait, $method, $sys, usize);
+        impl_binary_op!(@rhs $trait, $method, $sys, u64);
+        impl_binary_op!(@rhs $trait, $method, $sys, NativePointer);
+        impl_binary_op!(@rhs $trait, $method, $sys, &NativePointer);
+    };
+    (@rhs $trait:ident, $method:ident, $sys:ident, $rhs:ty) => {
+        impl ops::$trait<$rhs> for NativePointer {
+            type Output = NativePointer;
+
+            fn $method(self, rhs: $rhs) -> NativePointer {
+                NativePointer(self.0.$sys(&rhs.to_operand()))
+            }
+        }
+
+        impl ops::$trait<$rhs> for &NativePointer {
+            type Output = NativePointer;
+
+            fn $method(self, rhs: $rhs) -> NativePointer {
+                NativePointer(self.0.$sys(&rhs.to_operand()))
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, add);
+impl_binary_op!(Sub, sub, sub);
+impl_binary_op!(BitAnd, bitand, and);
+impl_binary_op!(BitOr, bitor, or);
+impl_binary_op!(BitXor, bitxor, xor);
+
+macro_rules! impl_shift_op {
+    ($trait:ident, $method:ident, $sys:ident) => {
+        impl ops::$trait<u32> for NativePointer {
+            type Output = NativePointer;
+
+            fn $method(self, rhs: u32) -> NativePointer {
+                NativePointer(self.0.$sys(rhs))
+            }
+        }
+
+        impl ops::$trait<u32> for &NativePointer {
+            type Output = NativePointer;
+
+            fn $method(self, rhs: u32) -> NativePointer {
+                NativePointer(self.0.$sys(rhs))
+            }
+        }
+    };
+}
+
+impl_shift_op!(Shl, shl, shl);
+impl_shift_op!(Shr, shr, shr);
+
+impl ops::Not for NativePointer {
+    type Output = NativePointer;
+
+    fn not(self) -> NativePointer {
+        NativePointer(self.0.not())
+    }
+}
+
+impl ops::Not for &NativePointer {
+    type Output = NativePointer;
+
+    fn not(self) -> NativePointer {
+        NativePointer(self.0.not())
+    }
+}
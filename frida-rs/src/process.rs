@@ -5,8 +5,12 @@
 //![https://frida.re/docs/javascript-api/#process](https://frida.re/docs/javascript-api/#process).
 
 use crate::fromsys::FromSys;
+use crate::memory;
+use crate::memory::{Pattern, Protection};
 use crate::module;
+use crate::modulemap::ModuleMap;
 use crate::nativepointer;
+use crate::nativepointer::NativePointer;
 use crate::range::RangeDetails;
 use crate::thread;
 use frida_rs_sys::process;
@@ -140,13 +144,14 @@ pub fn get_range_by_address(address: &nativepointer::NativePointer) -> Option<Ra
 
 ///Get all memory ranges satisfying `protection`.
 ///
-///`protection` is a string with the form "rwx" where "rw-" means "must be
-///at least readable and writable."
+///`protection` is the minimum protection of the returned ranges, i.e.
+///`Protection::READ | Protection::WRITE` means "must be at least readable and
+///writable."
 ///
 ///This is the equivalent to calling `Process.enumerateRanges()` in the
 ///JavaScript API.
-pub fn enumerate_ranges(protection: &str) -> Vec<RangeDetails> {
-    frida_rs_sys::process::enumerate_ranges(protection)
+pub fn enumerate_ranges(protection: Protection) -> Vec<RangeDetails> {
+    frida_rs_sys::process::enumerate_ranges(&protection.to_string())
         .iter()
         .map(frida_rs_sys::range::RangeDetails::from)
         .map(crate::range::RangeDetails::from)
@@ -164,3 +169,136 @@ pub fn enumerate_malloc_ranges() -> Vec<RangeDetails> {
         .map(crate::range::RangeDetails::from)
         .collect()
 }
+
+///Match found by [`scan_ranges`](crate::process::scan_ranges).
+pub struct RangeMatch {
+    ///Address of the match.
+    pub address: NativePointer,
+    ///Size of the match in bytes.
+    pub size: usize,
+    ///Memory range containing the match.
+    pub range: RangeDetails,
+    ///Name of the module containing the match, if any.
+    pub module: Option<String>,
+}
+
+///Iterator over the matches found by
+///[`scan_ranges`](crate::process::scan_ranges).
+pub struct ScanRanges {
+    ranges: std::vec::IntoIter<RangeDetails>,
+    pattern: Pattern,
+    modules: ModuleMap,
+    restricted: bool,
+    matches: std::vec::IntoIter<RangeMatch>,
+}
+
+impl ScanRanges {
+    ///Only scan ranges that belong to a module in `modules`.
+    pub fn in_modules(mut self, modules: ModuleMap) -> Self {
+        self.modules = modules;
+        self.restricted = true;
+        self
+    }
+
+    ///Scan `range`, leaving out the pages that are no longer readable.
+    fn scan_range(&self, range: RangeDetails) -> Vec<RangeMatch> {
+        if self.restricted && !self.modules.has(&range.base) {
+            return Vec::new();
+        }
+
+        // Ranges may be unmapped or have their protection changed after they
+        // were enumerated, and guard pages fault when read.
+        match memory::query_protection(&range.base) {
+            Ok(p) if p.contains(Protection::READ) => {}
+            _ => return Vec::new(),
+        }
+
+        let matches = match memory::scan(&range.base, range.size, &self.pattern) {
+            Ok(m) => m,
+            // A page further into the range faulted, scan the readable parts
+            // on their own.
+            Err(_) => readable_runs(&range)
+                .into_iter()
+                .filter_map(|(offset, size)| {
+                    memory::scan(&(&range.base + offset), size, &self.pattern).ok()
+                })
+                .flatten()
+                .collect(),
+        };
+
+        let module = self.modules.get_name(&range.base);
+        matches
+            .into_iter()
+            .map(|m| RangeMatch {
+                address: m.address,
+                size: m.size,
+                range: range.clone(),
+                module: module.clone(),
+            })
+            .collect()
+    }
+}
+
+///Get the offsets and sizes of the runs of readable pages in `range`.
+///
+///Adjacent readable pages are merged so that matches crossing a page boundary
+///are still found.
+fn readable_runs(range: &RangeDetails) -> Vec<(usize, usize)> {
+    let page_size = get_page_size();
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut offset = 0;
+
+    while offset < range.size {
+        let len = (page_size - (range.base.to_u64() as usize + offset) % page_size)
+            .min(range.size - offset);
+
+        if (&range.base + offset).read_u8().is_ok() {
+            match runs.last_mut() {
+                Some((o, s)) if *o + *s == offset => *s += len,
+                _ => runs.push((offset, len)),
+            }
+        }
+
+        offset += len;
+    }
+
+    runs
+}
+
+impl Iterator for ScanRanges {
+    type Item = RangeMatch;
+
+    fn next(&mut self) -> Option<RangeMatch> {
+        loop {
+            if let Some(m) = self.matches.next() {
+                return Some(m);
+            }
+
+            let range = self.ranges.next()?;
+            self.matches = self.scan_range(range).into_iter();
+        }
+    }
+}
+
+///Scan all memory ranges satisfying `protection` for `pattern`.
+///
+///Ranges are scanned lazily as the returned iterator is advanced. Ranges that
+///are not readable, such as guard pages, or that disappear while scanning are
+///skipped. Every match is annotated with the range and module containing it.
+///Use [`in_modules`](crate::process::ScanRanges::in_modules) to only scan
+///ranges belonging to the modules of a [`ModuleMap`](crate::modulemap::ModuleMap).
+///
+///```
+///for m in process::scan_ranges(Protection::READ, &pattern!("de ad be ef")) {
+///    console_log!("{} in {:?}", m.address, m.module);
+///}
+///```
+pub fn scan_ranges(protection: Protection, pattern: &Pattern) -> ScanRanges {
+    ScanRanges {
+        ranges: enumerate_ranges(protection).into_iter(),
+        pattern: pattern.clone(),
+        modules: ModuleMap::new(),
+        restricted: false,
+        matches: Vec::new().into_iter(),
+    }
+}
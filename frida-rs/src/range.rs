@@ -1,10 +1,13 @@
 use crate::fromsys::FromSys;
+use crate::memory::Protection;
 use crate::nativepointer::NativePointer;
+use serde_derive::{Deserialize, Serialize};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RangeDetails {
     pub base: NativePointer,
     pub size: usize,
-    pub protection: String,
+    pub protection: Protection,
     pub file: Option<FileMapping>,
 }
 
@@ -13,12 +16,13 @@ impl From<frida_rs_sys::range::RangeDetails> for RangeDetails {
         RangeDetails {
             base: NativePointer::from_sys(m.base()),
             size: m.size(),
-            protection: m.protection(),
+            protection: m.protection().parse().unwrap(),
             file: m.file().map(FileMapping::from),
         }
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct FileMapping {
     pub path: String,
     pub offset: usize,
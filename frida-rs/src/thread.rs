@@ -7,6 +7,7 @@
 use crate::cpu::CpuContext;
 use crate::fromsys::FromSys;
 use crate::NativePointer;
+use serde_derive::{Deserialize, Serialize};
 use std::fmt;
 use std::str;
 use std::str::FromStr;
@@ -14,6 +15,8 @@ use wasm_bindgen::prelude::*;
 
 ///Status of a thread. Accessed through the
 ///[`ThreadDetails`](crate::thread::ThreadDetails) struct.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ThreadState {
     Running,
     Stopped,
@@ -52,6 +55,7 @@ impl fmt::Display for ThreadState {
 
 ///Detailed information about a thread. Obtained by calling
 ///[`Process.enumerateThreads`](crate::process::enumerate_threads).
+#[derive(Serialize)]
 pub struct ThreadDetails {
     pub id: u32,
     pub state: ThreadState,